@@ -1,43 +1,115 @@
-use cosmwasm_std::{DepsMut, StdResult, Response, MessageInfo, Coin};
+use cosmwasm_std::{Addr, Deps, DepsMut, Env, Response, MessageInfo, Coin, Decimal, StdResult, Storage, Order, Uint128, StakingMsg, Reply, SubMsgResult, CosmosMsg, BankMsg, WasmMsg};
 use cw_storage_plus::Item;
 use cw2::{set_contract_version, get_contract_version};
+use cw_utils::Duration;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-use crate::msg::{MigrateMsg, Parent};
+use crate::msg::{MigrateMsg, Parent, DenomVesting, WithdrawLimits, Owners, Role, Governance, Unbonding, Sink, CountingMode, AccessMode, LimitAction, Milestone, MilestoneAction, IbcCounterparty};
 use crate::{msg::InstantiateMsg};
-use crate::state::{State, STATE, OWNER, PARENT_DONATION, ParentDonation};
+use crate::state::{State, STATE, OWNERS, OwnerSet, ROLES, PAUSED, GOVERNANCE, CONTRIBUTIONS, TOTAL_CONTRIBUTED, PARENT_DONATION, ParentDonation, NEXT_PAYOUT, REFUND_WINDOW, REFUNDABLE, RefundableDonation, LOCKED_REFUNDS, VESTING, VestingSchedule, WITHDRAWN, WITHDRAW_WINDOW, WithdrawWindow, UNBONDING_PERIOD, UNBONDING, SINKS, IBC_TIMEOUT, IBC_DESTINATIONS, IBC_COUNTERPARTIES, PENDING_SHARES, VOUCHER_KEY, MATCHING_POOL, CAMPAIGN_BALANCE, COUNTING_MODE, COUNTER_HISTORY, DonorTotal, donor_totals, REFERRAL_PART, ACCESS_MODE, ALLOWLIST, BLOCKLIST, RATE_LIMIT, DONOR_ACTIVITY, MILESTONES, MilestoneStatus};
 use crate::error::ContractError;
 
+#[cfg(test)]
+mod tests;
+
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const DEFAULT_PROPOSAL_DURATION: Duration = Duration::Time(7 * 24 * 60 * 60);
+const DEFAULT_UNBONDING_PERIOD: u64 = 21 * 24 * 60 * 60;
+const DEFAULT_IBC_TIMEOUT: u64 = 60 * 60;
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+const MAX_MEMO_LEN: usize = 256;
+const ROUND_HOOK_REPLY_ID: u64 = 1;
+const PARENT_DONATION_REPLY_ID: u64 = 2;
+const MILESTONE_REPLY_FLAG: u64 = 1 << 63;
+const MAX_DONATION_COUNT: u64 = 1_000;
     
-pub fn instantiate(deps: DepsMut, info: MessageInfo, msg: InstantiateMsg) -> StdResult<Response> {
+pub fn instantiate(mut deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     STATE.save(
         deps.storage,
         &State {
-            counter: 0,
             minimal_donation: msg.minimal_donation,
-            donating_parent: msg.parent.as_ref().map(|p| p.donating_period),
+            donating_parent: donating_parent(msg.parent.as_ref()),
         },
     )?;
-    OWNER.save(deps.storage, &info.sender)?;
+    record_counter(deps.storage, &env, 0)?;
 
-    if let Some(parent) = msg.parent {
-        PARENT_DONATION.save(
+    let owners = match msg.owners {
+        Some(owners) => owner_set(deps.as_ref(), owners)?,
+        None => OwnerSet {
+            members: vec![info.sender],
+            threshold: 1,
+            proposal_duration: DEFAULT_PROPOSAL_DURATION,
+        },
+    };
+    OWNERS.save(deps.storage, &owners)?;
+
+    if let Some(refund_window) = msg.refund_window {
+        REFUND_WINDOW.save(deps.storage, &refund_window)?;
+    }
+
+    if let Some(vesting) = msg.vesting {
+        VESTING.save(
             deps.storage,
-            &ParentDonation {
-                address: deps.api.addr_validate(&parent.addr)?,
-                donating_parent_period: parent.donating_period,
-                part: parent.part,
+            &VestingSchedule {
+                start: env.block.time,
+                duration: vesting.duration,
+                cliff: vesting.cliff.unwrap_or_default(),
             },
         )?;
     }
+
+    if let Some(governance) = msg.governance {
+        save_governance(deps.storage, governance)?;
+    }
+
+    if let Some(unbonding_period) = msg.unbonding_period {
+        UNBONDING_PERIOD.save(deps.storage, &unbonding_period)?;
+    }
+
+    if let Some(sinks) = msg.sinks {
+        save_sinks(deps.storage, sinks)?;
+    }
+
+    if let Some(ibc_timeout) = msg.ibc_timeout {
+        IBC_TIMEOUT.save(deps.storage, &ibc_timeout)?;
+    }
+
+    if let Some(voucher_key) = msg.voucher_key {
+        VOUCHER_KEY.save(deps.storage, &voucher_key)?;
+    }
+
+    if let Some(counting_mode) = msg.counting_mode {
+        save_counting_mode(deps.storage, counting_mode)?;
+    }
+
+    if let Some(referral_part) = msg.referral_part {
+        save_referral_part(deps.storage, referral_part)?;
+    }
+
+    if let Some(access_mode) = msg.access_mode {
+        ACCESS_MODE.save(deps.storage, &access_mode)?;
+    }
+
+    if let Some(rate_limit) = msg.rate_limit {
+        RATE_LIMIT.save(deps.storage, &rate_limit)?;
+    }
+
+    if let Some(parent) = msg.parent {
+        save_parent(deps.branch(), &env, parent)?;
+    }
+
+    if let Some(milestones) = msg.milestones {
+        save_milestones(deps.storage, 0, milestones)?;
+    }
     Ok(Response::new())
 }
 
-pub fn migrate(mut deps: DepsMut, msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(mut deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     let contract = get_contract_version(deps.storage)?;
 
     if contract.contract != CONTRACT_NAME {
@@ -45,8 +117,9 @@ pub fn migrate(mut deps: DepsMut, msg: MigrateMsg) -> Result<Response, ContractE
     }
 
     let resp = match contract.version.as_str() {
-        "0.1.0" => migrate_0_1_0(deps.branch(), msg.parent)?,
-        "0.2.0" => migrate_0_2_0(deps.branch(), msg.parent)?,
+        "0.1.0" => migrate_0_1_0(deps.branch(), &env, msg.parent)?,
+        "0.2.0" => migrate_0_2_0(deps.branch(), &env, msg.parent)?,
+        "0.3.0" => migrate_0_3_0(deps.branch(), &env)?,
         version if version == CONTRACT_VERSION => return Ok(Response::new()),
         _ => return Err(ContractError::InvalidVersion(contract.version.to_string())),
     };
@@ -55,7 +128,7 @@ pub fn migrate(mut deps: DepsMut, msg: MigrateMsg) -> Result<Response, ContractE
     Ok(resp)
 }
 
-pub fn migrate_0_1_0(deps: DepsMut, parent: Option<Parent>) -> StdResult<Response> {
+pub fn migrate_0_1_0(deps: DepsMut, env: &Env, parent: Option<Parent>) -> Result<Response, ContractError> {
     const COUNTER: Item<u64> = Item::new("counter");
     const MINIMAL_DONATION: Item<Coin> = Item::new("minimal_donation");
 
@@ -65,27 +138,21 @@ pub fn migrate_0_1_0(deps: DepsMut, parent: Option<Parent>) -> StdResult<Respons
     STATE.save(
         deps.storage, 
         &State {
-            counter,
             minimal_donation,
-            donating_parent: parent.as_ref().map(|p| p.donating_period),
+            donating_parent: donating_parent(parent.as_ref()),
         },
     )?;
+    record_counter(deps.storage, env, counter)?;
+    migrate_owner(deps.storage)?;
 
     if let Some(parent) = parent {
-        PARENT_DONATION.save(
-            deps.storage,
-            &ParentDonation {
-                address: deps.api.addr_validate(&parent.addr)?,
-                donating_parent_period: parent.donating_period,
-                part: parent.part,
-            },
-        )?;
+        save_parent(deps, env, parent)?;
     }
 
     Ok(Response::new())
 }
 
-pub fn migrate_0_2_0(deps: DepsMut, parent: Option<Parent>) -> StdResult<Response> {
+pub fn migrate_0_2_0(deps: DepsMut, env: &Env, parent: Option<Parent>) -> Result<Response, ContractError> {
     #[derive(Deserialize, Serialize)]
     struct OldState {
         counter: u64,
@@ -97,99 +164,2581 @@ pub fn migrate_0_2_0(deps: DepsMut, parent: Option<Parent>) -> StdResult<Respons
     STATE.save(
         deps.storage, 
         &State {
-            counter: state.counter,
             minimal_donation: state.minimal_donation,
-            donating_parent: parent.as_ref().map(|p| p.donating_period),
+            donating_parent: donating_parent(parent.as_ref()),
         },
     )?;
+    record_counter(deps.storage, env, state.counter)?;
+    migrate_owner(deps.storage)?;
 
     if let Some(parent) = parent {
-        PARENT_DONATION.save(
-            deps.storage,
-            &ParentDonation {
-                address: deps.api.addr_validate(&parent.addr)?,
-                donating_parent_period: parent.donating_period,
-                part: parent.part,
-            },
-        )?;
+        save_parent(deps, env, parent)?;
     }
 
     Ok(Response::new())
 }
 
-pub mod query {
-    use cosmwasm_std::{Deps, StdResult};
+pub fn migrate_0_3_0(deps: DepsMut, env: &Env) -> Result<Response, ContractError> {
+    #[derive(Deserialize, Serialize)]
+    struct OldState {
+        counter: u64,
+        minimal_donation: Coin,
+        donating_parent: Option<u64>,
+    }
+    const OLD_STATE: Item<OldState> = Item::new("state");
+    let state = OLD_STATE.load(deps.storage)?;
+
+    STATE.save(
+        deps.storage,
+        &State {
+            minimal_donation: state.minimal_donation,
+            donating_parent: state.donating_parent,
+        },
+    )?;
+    record_counter(deps.storage, env, state.counter)?;
+    migrate_owner(deps.storage)?;
 
-    use crate::{msg::ValueResp, state::STATE};
+    Ok(Response::new())
+}
 
-    pub fn value(deps: Deps) -> StdResult<ValueResp> {
-        let value = STATE.load(deps.storage)?.counter;
-        Ok(ValueResp { value})
+pub fn reply(deps: DepsMut, msg: Reply) -> Result<Response, ContractError> {
+    match (msg.id, msg.result) {
+        (ROUND_HOOK_REPLY_ID, SubMsgResult::Err(err)) => Ok(Response::new().add_attribute("action", "round_hook_failed").add_attribute("error", err)),
+        (PARENT_DONATION_REPLY_ID, SubMsgResult::Err(err)) => Ok(Response::new().add_attribute("action", "parent_donation_failed").add_attribute("error", err)),
+        (id, SubMsgResult::Err(err)) if id & MILESTONE_REPLY_FLAG != 0 => milestone_failed(deps, id & !MILESTONE_REPLY_FLAG, err),
+        (id, _) => Err(ContractError::UnknownReply { id }),
     }
 }
 
-pub mod exec {
-    use cosmwasm_std::{DepsMut, Env, StdResult, Response, MessageInfo, BankMsg, WasmMsg, to_binary};
-    use crate::msg::ExecMsg;
+fn milestone_failed(deps: DepsMut, threshold: u64, error: String) -> Result<Response, ContractError> {
+    let mut milestone = MILESTONES.load(deps.storage, threshold)?;
+    if let MilestoneAction::Message { msg } = &milestone.action {
+        for coin in milestone_funds(msg) {
+            WITHDRAWN.update(deps.storage, &coin.denom, |withdrawn| -> StdResult<_> {
+                Ok(withdrawn.unwrap_or_default().saturating_sub(coin.amount))
+            })?;
+        }
+    }
+    milestone.failed = Some(error.clone());
+    MILESTONES.save(deps.storage, threshold, &milestone)?;
 
-    use crate::error::ContractError;
-    use crate::state::PARENT_DONATION;
-    use crate::{state::{STATE, OWNER}};
+    let resp = Response::new()
+        .add_attribute("action", "milestone_failed")
+        .add_attribute("threshold", threshold.to_string())
+        .add_attribute("error", error);
+    Ok(resp)
+}
 
-    pub fn donate(deps: DepsMut, env: Env, info: MessageInfo) -> StdResult<Response> {
-        let mut state = STATE.load(deps.storage)?;
-        let mut resp = Response::default();
+fn migrate_owner(storage: &mut dyn Storage) -> StdResult<()> {
+    const OWNER: Item<Addr> = Item::new("owner");
 
-        if info.funds.iter().any(|coin| coin.denom == state.minimal_donation.denom && coin.amount >= state.minimal_donation.amount) {
-            state.counter += 1;
+    let owner = OWNER.load(storage)?;
+    OWNER.remove(storage);
 
-            if let Some(parent) = &mut state.donating_parent {
-                *parent -= 1;
+    OWNERS.save(
+        storage,
+        &OwnerSet {
+            members: vec![owner],
+            threshold: 1,
+            proposal_duration: DEFAULT_PROPOSAL_DURATION,
+        },
+    )
+}
 
-                if *parent == 0 {
-                    let parent_donation = PARENT_DONATION.load(deps.storage)?;
-                    *parent = parent_donation.donating_parent_period;
+fn owner_set(deps: Deps, owners: Owners) -> Result<OwnerSet, ContractError> {
+    let mut members = owners
+        .members
+        .iter()
+        .map(|member| deps.api.addr_validate(member))
+        .collect::<StdResult<Vec<_>>>()?;
+    members.sort();
+    members.dedup();
 
-                    let funds = deps.querier.query_all_balances(env.contract.address)?.into_iter().map(|mut coin| {
-                        coin.amount = coin.amount * parent_donation.part;
-                        coin
-                    }).collect();
+    if owners.threshold == 0 || owners.threshold > members.len() as u64 {
+        return Err(ContractError::InvalidThreshold {
+            threshold: owners.threshold,
+            members: members.len() as u64,
+        });
+    }
 
-                    let msg = WasmMsg::Execute {
-                        contract_addr: parent_donation.address.to_string(), 
-                        msg: to_binary(&ExecMsg::Donate {})?, 
-                        funds: funds,
-                    };
+    Ok(OwnerSet {
+        members,
+        threshold: owners.threshold,
+        proposal_duration: owners.proposal_duration,
+    })
+}
 
-                    resp = resp
-                        .add_message(msg)
-                        .add_attribute("donated_to_parent", parent_donation.address.to_string());
+fn has_role(deps: Deps, addr: &Addr, role: Role) -> StdResult<bool> {
+    let owners = OWNERS.load(deps.storage)?;
+    if owners.members.contains(addr) && (role == Role::Owner || owners.threshold == 1) {
+        return Ok(true);
+    }
+
+    let roles = ROLES.may_load(deps.storage, addr)?.unwrap_or_default();
+    Ok(roles.has(role))
+}
+
+fn ensure_role(deps: Deps, sender: &Addr, role: Role) -> Result<(), ContractError> {
+    if !has_role(deps, sender, role)? {
+        return Err(ContractError::Unauthorized { required_role: role });
+    }
+    Ok(())
+}
+
+fn save_governance(storage: &mut dyn Storage, governance: Governance) -> Result<(), ContractError> {
+    if governance.quorum > Decimal::one() || governance.threshold > Decimal::one() {
+        return Err(ContractError::InvalidGovernance {});
+    }
+
+    GOVERNANCE.save(storage, &governance)?;
+    Ok(())
+}
+
+fn save_sinks(storage: &mut dyn Storage, sinks: Vec<Sink>) -> Result<(), ContractError> {
+    let total = sinks.iter().fold(Decimal::zero(), |total, sink| total + sink.part());
+    if total > Decimal::one() {
+        return Err(ContractError::InvalidSinkShares { total });
+    }
+
+    if sinks.is_empty() {
+        SINKS.remove(storage);
+    } else {
+        SINKS.save(storage, &sinks)?;
+    }
+    Ok(())
+}
+
+fn load_counter(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(COUNTER_HISTORY.may_load(storage)?.unwrap_or_default())
+}
+
+fn record_counter(storage: &mut dyn Storage, env: &Env, counter: u64) -> StdResult<()> {
+    COUNTER_HISTORY.save(storage, &counter, env.block.height)
+}
+
+fn counting_mode(storage: &dyn Storage) -> StdResult<CountingMode> {
+    Ok(COUNTING_MODE.may_load(storage)?.unwrap_or(CountingMode::Flat))
+}
+
+fn donation_count(storage: &dyn Storage, minimal_donation: &Coin, funds: &[Coin]) -> StdResult<u64> {
+    let amount = match funds.iter().find(|coin| coin.denom == minimal_donation.denom && coin.amount >= minimal_donation.amount) {
+        Some(coin) => coin.amount,
+        None => return Ok(0),
+    };
+
+    let count = match counting_mode(storage)? {
+        CountingMode::Flat => 1,
+        CountingMode::Proportional { .. } if minimal_donation.amount.is_zero() => 1,
+        CountingMode::Proportional { cap } => {
+            let count = u64::try_from((amount / minimal_donation.amount).u128()).unwrap_or(u64::MAX);
+            count.min(cap.unwrap_or(MAX_DONATION_COUNT).min(MAX_DONATION_COUNT))
+        }
+    };
+    Ok(count.max(1))
+}
+
+fn save_counting_mode(storage: &mut dyn Storage, mode: CountingMode) -> Result<(), ContractError> {
+    if let CountingMode::Proportional { cap: Some(cap) } = mode {
+        if cap > MAX_DONATION_COUNT {
+            return Err(ContractError::InvalidCountingCap { cap, max: MAX_DONATION_COUNT });
+        }
+    }
+    COUNTING_MODE.save(storage, &mode)?;
+    Ok(())
+}
+
+fn save_referral_part(storage: &mut dyn Storage, part: Decimal) -> Result<(), ContractError> {
+    if part > Decimal::one() {
+        return Err(ContractError::InvalidReferralPart { part });
+    }
+    REFERRAL_PART.save(storage, &part)?;
+    Ok(())
+}
+
+fn ensure_no_governance(deps: Deps) -> Result<(), ContractError> {
+    if GOVERNANCE.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::SpendingProposalRequired {});
+    }
+    Ok(())
+}
+
+fn add_contribution(storage: &mut dyn Storage, env: &Env, donor: &Addr, funds: &[Coin]) -> StdResult<()> {
+    let height = env.block.height;
+    for coin in funds {
+        let amount = CONTRIBUTIONS.update(storage, (donor, &coin.denom), height, |amount| -> StdResult<_> {
+            Ok(amount.unwrap_or_default() + coin.amount)
+        })?;
+        save_donor_total(storage, donor, &coin.denom, amount)?;
+        TOTAL_CONTRIBUTED.update(storage, &coin.denom, height, |amount| -> StdResult<_> {
+            Ok(amount.unwrap_or_default() + coin.amount)
+        })?;
+    }
+    Ok(())
+}
+
+fn remove_contribution(storage: &mut dyn Storage, env: &Env, donor: &Addr, funds: &[Coin]) -> StdResult<()> {
+    let height = env.block.height;
+    for coin in funds {
+        let amount = CONTRIBUTIONS.update(storage, (donor, &coin.denom), height, |amount| -> StdResult<_> {
+            Ok(amount.unwrap_or_default().saturating_sub(coin.amount))
+        })?;
+        save_donor_total(storage, donor, &coin.denom, amount)?;
+        TOTAL_CONTRIBUTED.update(storage, &coin.denom, height, |amount| -> StdResult<_> {
+            Ok(amount.unwrap_or_default().saturating_sub(coin.amount))
+        })?;
+    }
+    Ok(())
+}
+
+fn save_donor_total(storage: &mut dyn Storage, donor: &Addr, denom: &str, amount: Uint128) -> StdResult<()> {
+    if amount.is_zero() {
+        return donor_totals().remove(storage, (donor, denom));
+    }
+
+    let total = DonorTotal { donor: donor.clone(), denom: denom.to_owned(), amount };
+    donor_totals().save(storage, (donor, denom), &total)
+}
+
+fn ensure_not_paused(deps: Deps) -> Result<(), ContractError> {
+    if PAUSED.may_load(deps.storage)?.unwrap_or(false) {
+        return Err(ContractError::Paused {});
+    }
+    Ok(())
+}
+
+fn ensure_no_proposal_required(deps: Deps) -> Result<(), ContractError> {
+    let owners = OWNERS.load(deps.storage)?;
+    if owners.threshold > 1 {
+        return Err(ContractError::ProposalRequired { threshold: owners.threshold });
+    }
+    Ok(())
+}
+
+fn access_mode(storage: &dyn Storage) -> StdResult<AccessMode> {
+    Ok(ACCESS_MODE.may_load(storage)?.unwrap_or(AccessMode::Open))
+}
+
+fn ensure_allowed(deps: Deps, donor: &Addr) -> Result<(), ContractError> {
+    let allowed = match access_mode(deps.storage)? {
+        AccessMode::Open => true,
+        AccessMode::Allowlist => ALLOWLIST.has(deps.storage, donor),
+        AccessMode::Blocklist => !BLOCKLIST.has(deps.storage, donor),
+    };
+    if !allowed {
+        return Err(ContractError::DonorNotAllowed { donor: donor.clone() });
+    }
+    Ok(())
+}
+
+fn rate_limit(storage: &mut dyn Storage, env: &Env, donor: &Addr) -> Result<bool, ContractError> {
+    let limit = match RATE_LIMIT.may_load(storage)? {
+        Some(limit) => limit,
+        None => return Ok(true),
+    };
+    let mut activity = DONOR_ACTIVITY.may_load(storage, donor)?.unwrap_or_default();
+
+    let mut available_at = activity.cooldown_ends.filter(|ends| !ends.is_expired(&env.block));
+    if let Some(window) = &limit.window {
+        if !matches!(activity.window_ends, Some(ends) if !ends.is_expired(&env.block)) {
+            activity.window_ends = Some(window.duration.after(&env.block));
+            activity.window_counted = 0;
+        }
+        if activity.window_counted >= window.max_counted {
+            available_at = available_at.or(activity.window_ends);
+        }
+    }
+
+    if let Some(available_at) = available_at {
+        return match limit.on_exceeded {
+            LimitAction::Reject => Err(ContractError::RateLimited { donor: donor.clone(), available_at }),
+            LimitAction::Uncount => Ok(false),
+        };
+    }
+
+    activity.window_counted += 1;
+    activity.cooldown_ends = limit.cooldown.map(|cooldown| cooldown.after(&env.block));
+    DONOR_ACTIVITY.save(storage, donor, &activity)?;
+    Ok(true)
+}
+
+fn save_milestones(storage: &mut dyn Storage, counter: u64, milestones: Vec<Milestone>) -> Result<(), ContractError> {
+    for milestone in milestones {
+        let threshold = milestone.threshold;
+        if threshold <= counter {
+            return Err(ContractError::MilestonePassed { threshold, counter });
+        }
+        if MILESTONES.has(storage, threshold) {
+            return Err(ContractError::MilestoneExists { threshold });
+        }
+        match &milestone.action {
+            MilestoneAction::Event {} => {}
+            MilestoneAction::NotifyParent {} => {
+                if PARENT_DONATION.may_load(storage)?.is_none() {
+                    return Err(ContractError::NoParent {});
+                }
+            }
+            MilestoneAction::Message { msg } => {
+                if !matches!(msg, CosmosMsg::Bank(BankMsg::Send { .. }) | CosmosMsg::Wasm(WasmMsg::Execute { .. })) {
+                    return Err(ContractError::UnsupportedMilestoneMessage { threshold });
+                }
+                if threshold & MILESTONE_REPLY_FLAG != 0 {
+                    return Err(ContractError::MilestoneOutOfRange { threshold });
+                }
+                if GOVERNANCE.may_load(storage)?.is_some() {
+                    return Err(ContractError::SpendingProposalRequired {});
                 }
             }
+        }
 
-            STATE.save(deps.storage, &state)?;
+        MILESTONES.save(storage, threshold, &MilestoneStatus { action: milestone.action, reached_at: None, failed: None })?;
+    }
+    Ok(())
+}
+
+fn milestone_funds(msg: &CosmosMsg) -> Vec<Coin> {
+    match msg {
+        CosmosMsg::Bank(BankMsg::Send { amount, .. }) => amount.clone(),
+        CosmosMsg::Wasm(WasmMsg::Execute { funds, .. }) => funds.clone(),
+        _ => vec![],
+    }
+}
+
+fn donating_parent(parent: Option<&Parent>) -> Option<u64> {
+    parent
+        .filter(|p| p.payout_interval.is_none())
+        .map(|p| p.donating_period)
+}
+
+fn save_parent(deps: DepsMut, env: &Env, parent: Parent) -> Result<(), ContractError> {
+    if let Some(keeper_fee) = parent.keeper_fee {
+        if keeper_fee > Decimal::one() {
+            return Err(ContractError::InvalidKeeperFee(keeper_fee));
         }
+    }
 
-        resp = resp
-            .add_attribute("action", "donate")
-            .add_attribute("sender", info.sender.as_str())
-            .add_attribute("counter", state.counter.to_string());
-        
-        Ok(resp)
+    PARENT_DONATION.save(
+        deps.storage,
+        &ParentDonation {
+            address: match parent.ibc {
+                Some(_) => Addr::unchecked(parent.addr),
+                None => deps.api.addr_validate(&parent.addr)?,
+            },
+            donating_parent_period: parent.donating_period,
+            part: parent.part,
+            payout_interval: parent.payout_interval,
+            keeper_fee: parent.keeper_fee,
+            ibc: parent.ibc,
+        },
+    )?;
+
+    if let Some(interval) = parent.payout_interval {
+        NEXT_PAYOUT.save(deps.storage, &interval.after(&env.block))?;
     }
 
-    pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-        let owner = OWNER.load(deps.storage)?;
-        if info.sender != owner {
-            return Err(ContractError::Unauthorized {
-                owner: owner.into(),
-            });
+    Ok(())
+}
+
+fn expired_refunds(storage: &dyn Storage, env: &Env) -> StdResult<Vec<(u64, RefundableDonation)>> {
+    let mut expired = vec![];
+    for donation in REFUNDABLE.range(storage, None, None, Order::Ascending) {
+        let (donation_id, donation) = donation?;
+        if !donation.expires.is_expired(&env.block) {
+            break;
         }
+        expired.push((donation_id, donation));
+    }
+    Ok(expired)
+}
 
-        let funds = deps.querier.query_all_balances(&env.contract.address)?;
-        let bank_msg = BankMsg::Send { to_address: owner.to_string(), amount: funds };
+fn lock_refund(storage: &mut dyn Storage, funds: &[Coin]) -> StdResult<()> {
+    let mut locked = LOCKED_REFUNDS.may_load(storage)?.unwrap_or_default();
+    for coin in funds {
+        add_coin(&mut locked, coin);
+    }
+    LOCKED_REFUNDS.save(storage, &locked)
+}
 
-        let resp = Response::new().add_message(bank_msg).add_attribute("action", "withdraw").add_attribute("sender", info.sender.as_str());
-        Ok(resp)
+fn unlock_refund(storage: &mut dyn Storage, funds: &[Coin]) -> StdResult<()> {
+    let mut locked = LOCKED_REFUNDS.may_load(storage)?.unwrap_or_default();
+    for coin in funds {
+        sub_coin(&mut locked, coin);
+    }
+    locked.retain(|coin| !coin.amount.is_zero());
+    LOCKED_REFUNDS.save(storage, &locked)
+}
+
+fn release_expired_refunds(storage: &mut dyn Storage, env: &Env) -> StdResult<()> {
+    for (donation_id, donation) in expired_refunds(storage, env)? {
+        REFUNDABLE.remove(storage, donation_id);
+        unlock_refund(storage, &donation.locked())?;
+    }
+    Ok(())
+}
+
+fn available_balance(deps: Deps, env: &Env) -> StdResult<Vec<Coin>> {
+    let mut balance = deps.querier.query_all_balances(&env.contract.address)?;
+
+    let mut locked = LOCKED_REFUNDS.may_load(deps.storage)?.unwrap_or_default();
+    for (_, donation) in expired_refunds(deps.storage, env)? {
+        for coin in &donation.locked() {
+            sub_coin(&mut locked, coin);
+        }
+    }
+    for coin in &locked {
+        sub_coin(&mut balance, coin);
+    }
+
+    for share in PENDING_SHARES.range(deps.storage, None, None, Order::Ascending) {
+        let (_, share) = share?;
+        for locked in share.funds {
+            if let Some(coin) = balance.iter_mut().find(|coin| coin.denom == locked.denom) {
+                coin.amount = coin.amount.saturating_sub(locked.amount);
+            }
+        }
     }
-}
\ No newline at end of file
+
+    if let Some(pool) = MATCHING_POOL.may_load(deps.storage)? {
+        if let Some(coin) = balance.iter_mut().find(|coin| coin.denom == pool.remaining.denom) {
+            coin.amount = coin.amount.saturating_sub(pool.remaining.amount);
+        }
+    }
+
+    for locked in CAMPAIGN_BALANCE.may_load(deps.storage)?.unwrap_or_default() {
+        sub_coin(&mut balance, &locked);
+    }
+
+    balance.retain(|coin| !coin.amount.is_zero());
+    Ok(balance)
+}
+
+fn add_coin(coins: &mut Vec<Coin>, coin: &Coin) {
+    match coins.iter_mut().find(|existing| existing.denom == coin.denom) {
+        Some(existing) => existing.amount += coin.amount,
+        None => coins.push(coin.clone()),
+    }
+}
+
+fn sub_coin(coins: &mut [Coin], coin: &Coin) {
+    if let Some(existing) = coins.iter_mut().find(|existing| existing.denom == coin.denom) {
+        existing.amount = existing.amount.saturating_sub(coin.amount);
+    }
+}
+
+fn delegate_funds(deps: Deps, env: &Env, validator: String, amount: Coin) -> Result<StakingMsg, ContractError> {
+    let available = available_balance(deps, env)?
+        .into_iter()
+        .find(|coin| coin.denom == amount.denom)
+        .map(|coin| coin.amount)
+        .unwrap_or_default();
+    if amount.amount > available {
+        return Err(ContractError::InsufficientFunds {
+            available: Coin { denom: amount.denom.clone(), amount: available },
+            requested: amount,
+        });
+    }
+    Ok(StakingMsg::Delegate { validator, amount })
+}
+
+fn undelegate_funds(deps: DepsMut, env: &Env, validator: String, amount: Coin) -> Result<StakingMsg, ContractError> {
+    let mut unbonding = pending_unbonding(deps.as_ref(), env)?;
+    unbonding.push(Unbonding {
+        validator: validator.clone(),
+        amount: amount.clone(),
+        completes_at: env.block.time.plus_seconds(unbonding_period(deps.as_ref())?),
+    });
+    UNBONDING.save(deps.storage, &unbonding)?;
+    Ok(StakingMsg::Undelegate { validator, amount })
+}
+
+fn save_ibc_counterparty(storage: &mut dyn Storage, connection_id: &str, counterparty: Option<IbcCounterparty>) -> StdResult<()> {
+    match counterparty {
+        Some(counterparty) => IBC_COUNTERPARTIES.save(storage, connection_id, &counterparty),
+        None => {
+            IBC_COUNTERPARTIES.remove(storage, connection_id);
+            Ok(())
+        }
+    }
+}
+
+fn save_ibc_destination(storage: &mut dyn Storage, channel_id: &str, remote_address: Option<String>) -> StdResult<()> {
+    match remote_address {
+        Some(remote_address) => IBC_DESTINATIONS.save(storage, channel_id, &remote_address),
+        None => {
+            IBC_DESTINATIONS.remove(storage, channel_id);
+            Ok(())
+        }
+    }
+}
+
+fn ibc_denom(transfer_channel_id: &str, denom: &str) -> String {
+    let hash = Sha256::digest(format!("transfer/{}/{}", transfer_channel_id, denom));
+    let hash: String = hash.iter().map(|byte| format!("{:02X}", byte)).collect();
+    format!("ibc/{}", hash)
+}
+
+fn unbonding_period(deps: Deps) -> StdResult<u64> {
+    Ok(UNBONDING_PERIOD.may_load(deps.storage)?.unwrap_or(DEFAULT_UNBONDING_PERIOD))
+}
+
+fn ibc_timeout(deps: Deps) -> StdResult<u64> {
+    Ok(IBC_TIMEOUT.may_load(deps.storage)?.unwrap_or(DEFAULT_IBC_TIMEOUT))
+}
+
+fn pending_unbonding(deps: Deps, env: &Env) -> StdResult<Vec<Unbonding>> {
+    let mut unbonding = UNBONDING.may_load(deps.storage)?.unwrap_or_default();
+    unbonding.retain(|entry| entry.completes_at > env.block.time);
+    Ok(unbonding)
+}
+
+fn bonded_balance(deps: Deps, env: &Env) -> StdResult<Vec<Coin>> {
+    let mut bonded = vec![];
+    for delegation in deps.querier.query_all_delegations(&env.contract.address)? {
+        add_coin(&mut bonded, &delegation.amount);
+    }
+    Ok(bonded)
+}
+
+fn staked_balance(deps: Deps, env: &Env) -> StdResult<Vec<Coin>> {
+    let mut staked = bonded_balance(deps, env)?;
+    for entry in pending_unbonding(deps, env)? {
+        add_coin(&mut staked, &entry.amount);
+    }
+    Ok(staked)
+}
+
+fn vesting_status(deps: Deps, env: &Env) -> StdResult<Vec<DenomVesting>> {
+    let vesting = VESTING.may_load(deps.storage)?;
+    let balance = available_balance(deps, env)?;
+    let staked = staked_balance(deps, env)?;
+
+    let mut denoms: Vec<String> = balance.iter().map(|coin| coin.denom.clone()).collect();
+    for coin in &staked {
+        if !denoms.contains(&coin.denom) {
+            denoms.push(coin.denom.clone());
+        }
+    }
+    for denom in WITHDRAWN.keys(deps.storage, None, None, Order::Ascending) {
+        let denom = denom?;
+        if !denoms.contains(&denom) {
+            denoms.push(denom);
+        }
+    }
+
+    denoms.into_iter().map(|denom| {
+        let available = balance.iter().find(|coin| coin.denom == denom).map(|coin| coin.amount).unwrap_or_default();
+        let staked = staked.iter().find(|coin| coin.denom == denom).map(|coin| coin.amount).unwrap_or_default();
+        let withdrawn = WITHDRAWN.may_load(deps.storage, &denom)?.unwrap_or_default();
+        let total = available + staked + withdrawn;
+        let vested = match &vesting {
+            Some(vesting) => vesting.vested(total, env.block.time),
+            None => total,
+        };
+
+        Ok(DenomVesting { denom, vested, withdrawn, locked: total - vested })
+    }).collect()
+}
+
+fn withdraw_window(deps: Deps, env: &Env, limits: &WithdrawLimits) -> StdResult<WithdrawWindow> {
+    let window = WITHDRAW_WINDOW.may_load(deps.storage)?;
+    let window = match window {
+        Some(window) if env.block.time < window.start.plus_seconds(limits.window) => window,
+        window => WithdrawWindow {
+            start: env.block.time,
+            withdrawn: vec![],
+            last_withdraw: window.and_then(|window| window.last_withdraw),
+        },
+    };
+    Ok(window)
+}
+
+fn remaining_allowance(limits: &WithdrawLimits, window: &WithdrawWindow) -> Vec<Coin> {
+    limits.max_amounts.iter().map(|max| {
+        let withdrawn = window.withdrawn.iter().find(|coin| coin.denom == max.denom).map(|coin| coin.amount).unwrap_or_default();
+        Coin { denom: max.denom.clone(), amount: max.amount.saturating_sub(withdrawn) }
+    }).collect()
+}
+
+pub mod query {
+    use cosmwasm_std::{Coin, Deps, Env, Order, StdResult};
+    use cw_storage_plus::Bound;
+    use cw_utils::Expiration;
+
+    use crate::msg::{ValueResp, NextPayoutResp, VestingResp, WithdrawAllowanceResp, OwnersResp, ProposalResp, RolesResp, Role, SpendingProposalResp, ContributionsResp};
+    use crate::msg::{Beneficiary, BeneficiariesResp, StakingResp, SinksResp, VoucherResp, MatchingPoolResp, CampaignResp, CampaignParent, ConfigResp, ChangelogResp, CounterChange, DonationResp, DonationsResp, LeaderboardEntry, LeaderboardResp, ReferralsResp, AccessList, AccessListResp, MilestoneResp, MilestonesResp};
+    use crate::state::{STATE, NEXT_PAYOUT, PAYOUT_PENDING, WITHDRAW_LIMITS, OWNERS, PROPOSALS, SPENDING_PROPOSALS, CONTRIBUTIONS, BENEFICIARIES, SINKS, REDEEMED_VOUCHERS, MATCHING_POOL, ROUND_CONTRIBUTIONS, CAMPAIGNS, COUNTER_HISTORY, donation_log, donor_totals, REFERRAL_PART, REFERRALS, ALLOWLIST, BLOCKLIST, RATE_LIMIT, MILESTONES};
+    use super::{DEFAULT_LIMIT, MAX_LIMIT};
+
+    pub fn value(deps: Deps, campaign: Option<String>) -> StdResult<ValueResp> {
+        let value = match campaign {
+            Some(name) => CAMPAIGNS.load(deps.storage, &name)?.counter,
+            None => super::load_counter(deps.storage)?,
+        };
+        Ok(ValueResp { value})
+    }
+
+    pub fn next_payout(deps: Deps, env: Env) -> StdResult<NextPayoutResp> {
+        let donations_left = STATE.load(deps.storage)?.donating_parent;
+        let next_payout = NEXT_PAYOUT.may_load(deps.storage)?;
+        let due = PAYOUT_PENDING.may_load(deps.storage)?.unwrap_or(false)
+            || next_payout.unwrap_or(Expiration::Never {}).is_expired(&env.block);
+        Ok(NextPayoutResp { donations_left, next_payout, due })
+    }
+
+    pub fn vesting(deps: Deps, env: Env) -> StdResult<VestingResp> {
+        let denoms = super::vesting_status(deps, &env)?;
+        Ok(VestingResp { denoms })
+    }
+
+    pub fn withdraw_allowance(deps: Deps, env: Env) -> StdResult<WithdrawAllowanceResp> {
+        let limits = match WITHDRAW_LIMITS.may_load(deps.storage)? {
+            Some(limits) => limits,
+            None => return Ok(WithdrawAllowanceResp { remaining: vec![], window_resets_at: None, next_withdraw_at: None }),
+        };
+
+        let window = super::withdraw_window(deps, &env, &limits)?;
+        Ok(WithdrawAllowanceResp {
+            remaining: super::remaining_allowance(&limits, &window),
+            window_resets_at: Some(window.start.plus_seconds(limits.window)),
+            next_withdraw_at: window.last_withdraw.map(|last| last.plus_seconds(limits.cooldown)),
+        })
+    }
+
+    pub fn owners(deps: Deps) -> StdResult<OwnersResp> {
+        let owners = OWNERS.load(deps.storage)?;
+        Ok(OwnersResp {
+            members: owners.members,
+            threshold: owners.threshold,
+            proposal_duration: owners.proposal_duration,
+        })
+    }
+
+    pub fn spending_proposal(deps: Deps, id: u64) -> StdResult<SpendingProposalResp> {
+        let proposal = SPENDING_PROPOSALS.load(deps.storage, id)?;
+        Ok(SpendingProposalResp {
+            id,
+            proposer: proposal.proposer,
+            description: proposal.description,
+            amount: proposal.amount,
+            recipient: proposal.recipient,
+            yes: proposal.yes,
+            no: proposal.no,
+            abstain: proposal.abstain,
+            total_weight: proposal.total_weight,
+            expires: proposal.expires,
+            executed: proposal.executed,
+        })
+    }
+
+    pub fn contributions(deps: Deps, donor: String) -> StdResult<ContributionsResp> {
+        let donor = deps.api.addr_validate(&donor)?;
+        let contributions = CONTRIBUTIONS
+            .prefix(&donor)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+            .collect::<StdResult<_>>()?;
+        Ok(ContributionsResp { contributions })
+    }
+
+    pub fn round_contributions(deps: Deps, donor: String) -> StdResult<ContributionsResp> {
+        let donor = deps.api.addr_validate(&donor)?;
+        let contributions = ROUND_CONTRIBUTIONS
+            .prefix(&donor)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+            .collect::<StdResult<_>>()?;
+        Ok(ContributionsResp { contributions })
+    }
+
+    pub fn campaign(deps: Deps, name: String) -> StdResult<CampaignResp> {
+        let campaign = CAMPAIGNS.load(deps.storage, &name)?;
+        let parent = campaign.parent.map(|parent| CampaignParent {
+            addr: parent.address.into(),
+            donating_period: parent.donating_period,
+            part: parent.part,
+        });
+        Ok(CampaignResp {
+            name,
+            owner: campaign.owner,
+            minimal_donation: campaign.minimal_donation,
+            parent,
+            counter: campaign.counter,
+            balance: campaign.balance,
+        })
+    }
+
+    pub fn beneficiaries(deps: Deps) -> StdResult<BeneficiariesResp> {
+        let beneficiaries = BENEFICIARIES
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|beneficiary| Beneficiary { address: beneficiary.address.into(), weight: beneficiary.weight })
+            .collect();
+        Ok(BeneficiariesResp { beneficiaries })
+    }
+
+    pub fn staking(deps: Deps, env: Env) -> StdResult<StakingResp> {
+        Ok(StakingResp {
+            liquid: super::available_balance(deps, &env)?,
+            bonded: super::bonded_balance(deps, &env)?,
+            unbonding: super::pending_unbonding(deps, &env)?,
+        })
+    }
+
+    pub fn value_at(deps: Deps, height: u64) -> StdResult<ValueResp> {
+        let value = COUNTER_HISTORY.may_load_at_height(deps.storage, height.saturating_add(1))?.unwrap_or_default();
+        Ok(ValueResp { value })
+    }
+
+    pub fn changelog(deps: Deps, start_after: Option<u64>, limit: Option<u32>) -> StdResult<ChangelogResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let entries = COUNTER_HISTORY
+            .changelog()
+            .range(deps.storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+            .take(limit + 1)
+            .map(|item| item.map(|(height, change)| (height, change.old.unwrap_or_default())))
+            .collect::<StdResult<Vec<_>>>()?;
+        let current = COUNTER_HISTORY.may_load(deps.storage)?.unwrap_or_default();
+
+        let changes = entries
+            .iter()
+            .enumerate()
+            .take(limit)
+            .map(|(idx, (height, _))| CounterChange {
+                height: *height,
+                value: entries.get(idx + 1).map_or(current, |(_, old)| *old),
+            })
+            .collect();
+        Ok(ChangelogResp { changes })
+    }
+
+    pub fn donations(deps: Deps, start_after: Option<u64>, limit: Option<u32>, donor: Option<String>) -> StdResult<DonationsResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start = start_after.map(Bound::exclusive);
+
+        let entries = match donor {
+            Some(donor) => donation_log()
+                .idx
+                .donor
+                .prefix(deps.api.addr_validate(&donor)?)
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?,
+            None => donation_log()
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .collect::<StdResult<Vec<_>>>()?,
+        };
+
+        let donations = entries
+            .into_iter()
+            .map(|(id, donation)| DonationResp {
+                id,
+                donor: donation.donor,
+                funds: donation.funds,
+                memo: donation.memo,
+                campaign: donation.campaign,
+                height: donation.height,
+                time: donation.time,
+            })
+            .collect();
+        Ok(DonationsResp { donations })
+    }
+
+    pub fn leaderboard(deps: Deps, denom: String, limit: Option<u32>) -> StdResult<LeaderboardResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let donors = donor_totals()
+            .idx
+            .amount
+            .sub_prefix(denom)
+            .range(deps.storage, None, None, Order::Descending)
+            .take(limit)
+            .map(|item| item.map(|(_, total)| LeaderboardEntry { donor: total.donor, amount: total.amount }))
+            .collect::<StdResult<_>>()?;
+        Ok(LeaderboardResp { donors })
+    }
+
+    pub fn config(deps: Deps) -> StdResult<ConfigResp> {
+        let minimal_donation = STATE.load(deps.storage)?.minimal_donation;
+        let counting_mode = super::counting_mode(deps.storage)?;
+        let referral_part = REFERRAL_PART.may_load(deps.storage)?.unwrap_or_default();
+        let access_mode = super::access_mode(deps.storage)?;
+        let rate_limit = RATE_LIMIT.may_load(deps.storage)?;
+        Ok(ConfigResp { minimal_donation, counting_mode, referral_part, access_mode, rate_limit })
+    }
+
+    pub fn milestones(deps: Deps) -> StdResult<MilestonesResp> {
+        let (reached, pending): (Vec<_>, Vec<_>) = MILESTONES
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(threshold, milestone)| MilestoneResp { threshold, action: milestone.action, reached_at: milestone.reached_at, failed: milestone.failed }))
+            .collect::<StdResult<Vec<_>>>()?
+            .into_iter()
+            .partition(|milestone| milestone.reached_at.is_some());
+        Ok(MilestonesResp { reached, pending })
+    }
+
+    pub fn access_list(deps: Deps, list: AccessList, start_after: Option<String>, limit: Option<u32>) -> StdResult<AccessListResp> {
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+        let start_after = start_after.map(|address| deps.api.addr_validate(&address)).transpose()?;
+
+        let map = match list {
+            AccessList::Allowlist => ALLOWLIST,
+            AccessList::Blocklist => BLOCKLIST,
+        };
+        let addresses = map
+            .keys(deps.storage, start_after.as_ref().map(Bound::exclusive), None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<_>>()?;
+        Ok(AccessListResp { addresses })
+    }
+
+    pub fn referrals(deps: Deps, referrer: String) -> StdResult<ReferralsResp> {
+        let referrer = deps.api.addr_validate(&referrer)?;
+        let rewards = REFERRALS
+            .prefix(&referrer)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+            .collect::<StdResult<_>>()?;
+        Ok(ReferralsResp { rewards })
+    }
+
+    pub fn sinks(deps: Deps) -> StdResult<SinksResp> {
+        let sinks = SINKS.may_load(deps.storage)?.unwrap_or_default();
+        Ok(SinksResp { sinks })
+    }
+
+    pub fn voucher(deps: Deps, nonce: u64) -> StdResult<VoucherResp> {
+        let redeemed_by = REDEEMED_VOUCHERS.may_load(deps.storage, nonce)?;
+        Ok(VoucherResp { nonce, redeemed_by })
+    }
+
+    pub fn matching_pool(deps: Deps) -> StdResult<MatchingPoolResp> {
+        let pool = MATCHING_POOL.load(deps.storage)?;
+        Ok(MatchingPoolResp {
+            sponsor: pool.sponsor,
+            remaining: pool.remaining,
+            matched: pool.matched,
+            ratio: pool.ratio,
+            donor_cap: pool.donor_cap,
+            deadline: pool.deadline,
+        })
+    }
+
+    pub fn roles(deps: Deps, address: String) -> StdResult<RolesResp> {
+        let address = deps.api.addr_validate(&address)?;
+        let mut roles = vec![];
+        for role in [Role::Owner, Role::Treasurer, Role::ConfigManager, Role::Pauser] {
+            if super::has_role(deps, &address, role)? {
+                roles.push(role);
+            }
+        }
+        Ok(RolesResp { roles })
+    }
+
+    pub fn proposal(deps: Deps, id: u64) -> StdResult<ProposalResp> {
+        let proposal = PROPOSALS.load(deps.storage, id)?;
+        Ok(ProposalResp {
+            id,
+            proposer: proposal.proposer,
+            action: proposal.action,
+            approvals: proposal.approvals,
+            expires: proposal.expires,
+            executed: proposal.executed,
+        })
+    }
+}
+
+pub mod exec {
+    use cosmwasm_std::{Addr, Binary, Deps, DepsMut, Empty, Env, Event, StdResult, Storage, Response, MessageInfo, BankMsg, WasmMsg, CosmosMsg, SubMsg, Coin, Decimal, Order, Uint128, StakingMsg, DistributionMsg, IbcMsg, IbcTimeout, to_json_binary, to_json_vec};
+    use sha2::{Digest, Sha256};
+    use cw_storage_plus::Bound;
+    use cw_utils::Expiration;
+    use crate::msg::{ExecMsg, WithdrawLimits, ProposalAction, Role, VoteOption, Beneficiary, Sink, IbcWithdraw, IbcCounterparty, CountingPacket, Voucher, VoucherKey, RoundHookMsg, CampaignParent, CountingMode, AccessMode, AccessList, RateLimit, Milestone, MilestoneAction};
+
+    use crate::error::ContractError;
+    use crate::state::{PARENT_DONATION, NEXT_PAYOUT, PAYOUT_PENDING, ParentDonation, REFUND_WINDOW, LAST_DONATION_ID, REFUNDABLE, RefundableDonation, Referral, WITHDRAWN, WITHDRAW_LIMITS, WITHDRAW_WINDOW, WITHDRAW_RECIPIENT, IBC_DESTINATIONS, IBC_DONATIONS};
+
+    use super::{available_balance, ensure_no_proposal_required, release_expired_refunds, lock_refund, unlock_refund, vesting_status, withdraw_window, remaining_allowance, ensure_role, ensure_not_paused, ensure_no_governance, ensure_allowed, milestone_funds, add_contribution, remove_contribution, add_coin, sub_coin};
+    use super::{delegate_funds, undelegate_funds, save_ibc_destination, save_ibc_counterparty, save_sinks, ibc_timeout, donation_count, load_counter, record_counter, save_counting_mode, save_referral_part, rate_limit, save_milestones, MAX_MEMO_LEN, ROUND_HOOK_REPLY_ID, PARENT_DONATION_REPLY_ID, MILESTONE_REPLY_FLAG};
+    use crate::state::{STATE, OWNERS, PROPOSALS, LAST_PROPOSAL_ID, Proposal, ROLES, Roles, PAUSED};
+    use crate::state::{BENEFICIARIES, BeneficiaryShare, SINKS, LAST_PACKET_ID, PENDING_SHARES, PendingShare, VOUCHER_KEY, REDEEMED_VOUCHERS, MATCHING_POOL, MatchingPool, LAST_MATCHING_POOL_ID, MATCHED, MatchedDonation, ROUND_MANAGER, ROUND_CONTRIBUTIONS, CAMPAIGNS, CAMPAIGN_BALANCE, Campaign, CampaignParentDonation, LAST_LOGGED_DONATION_ID, Donation, donation_log, REFERRAL_PART, REFERRALS, ACCESS_MODE, ALLOWLIST, BLOCKLIST, RATE_LIMIT, MILESTONES};
+    use crate::state::{GOVERNANCE, SPENDING_PROPOSALS, LAST_SPENDING_PROPOSAL_ID, SpendingProposal, VOTES, CONTRIBUTIONS, TOTAL_CONTRIBUTED};
+
+    pub fn donate(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        memo: Option<String>,
+        beneficiary: Option<String>,
+        referrer: Option<String>,
+    ) -> Result<Response, ContractError> {
+        ensure_not_paused(deps.as_ref())?;
+        release_expired_refunds(deps.storage, &env)?;
+        ensure_memo(memo.as_deref())?;
+        let (credited, referrer) = donation_parties(deps.as_ref(), &info, beneficiary, referrer)?;
+
+        let mut state = STATE.load(deps.storage)?;
+        let mut counter = load_counter(deps.storage)?;
+        let mut resp = Response::default();
+
+        let mut count = donation_count(deps.storage, &state.minimal_donation, &info.funds)?;
+        if count > 0 && !rate_limit(deps.storage, &env, &info.sender)? {
+            count = 0;
+            resp = resp.add_attribute("rate_limited", info.sender.as_str());
+        }
+        let counted = count > 0;
+
+        let mut kept = info.funds.clone();
+        let mut referral = None;
+        if counted {
+            for sink in SINKS.may_load(deps.storage)?.unwrap_or_default() {
+                let sunk = sink_funds(&sink, &info.funds);
+                if sunk.is_empty() {
+                    continue;
+                }
+
+                for coin in &sunk {
+                    if let Some(kept) = kept.iter_mut().find(|kept| kept.denom == coin.denom) {
+                        kept.amount -= coin.amount;
+                    }
+                }
+                resp = resp.add_message(match sink {
+                    Sink::CommunityPool { .. } => CosmosMsg::from(DistributionMsg::FundCommunityPool { amount: sunk }),
+                    Sink::Burn { .. } => CosmosMsg::from(BankMsg::Burn { amount: sunk }),
+                });
+            }
+
+            if let Some(referrer) = &referrer {
+                let reward = referral_reward(deps.storage, referrer, &mut kept)?;
+                if !reward.is_empty() {
+                    resp = resp
+                        .add_message(BankMsg::Send { to_address: referrer.to_string(), amount: reward.clone() })
+                        .add_attribute("referrer", referrer.as_str());
+                    referral = Some(Referral { referrer: referrer.clone(), reward });
+                }
+            }
+            kept.retain(|coin| !coin.amount.is_zero());
+        }
+        add_contribution(deps.storage, &env, &credited, &kept)?;
+
+        let mut refundable = None;
+        if let Some(refund_window) = REFUND_WINDOW.may_load(deps.storage)? {
+            if counted && !kept.is_empty() {
+                let donation_id = LAST_DONATION_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+                LAST_DONATION_ID.save(deps.storage, &donation_id)?;
+                lock_refund(deps.storage, &kept)?;
+                REFUNDABLE.save(
+                    deps.storage,
+                    donation_id,
+                    &RefundableDonation {
+                        donor: info.sender.clone(),
+                        funds: kept.clone(),
+                        counted,
+                        count,
+                        beneficiary: (credited != info.sender).then(|| credited.clone()),
+                        expires: refund_window.after(&env.block),
+                        matched: None,
+                        referral,
+                    },
+                )?;
+
+                refundable = Some(donation_id);
+                resp = resp.add_attribute("donation_id", donation_id.to_string());
+            }
+        }
+
+        if counted {
+            let previous = counter;
+            counter = counter.checked_add(count).ok_or(ContractError::CounterOverflow {})?;
+            log_donation(deps.storage, &env, &credited, &info.funds, memo, None)?;
+
+            if let Some(parent) = &mut state.donating_parent {
+                *parent -= 1;
+
+                if *parent == 0 {
+                    let parent_donation = PARENT_DONATION.load(deps.storage)?;
+                    *parent = parent_donation.donating_parent_period;
+
+                    if parent_donation.keeper_fee.is_some() {
+                        PAYOUT_PENDING.save(deps.storage, &true)?;
+                    } else {
+                        resp = resp
+                            .add_submessages(donate_parent(deps.branch(), &env, &parent_donation, None)?)
+                            .add_attribute("donated_to_parent", parent_donation.address.to_string());
+                    }
+                }
+            } else if let Some(next_payout) = NEXT_PAYOUT.may_load(deps.storage)? {
+                let parent_donation = PARENT_DONATION.load(deps.storage)?;
+
+                if next_payout.is_expired(&env.block) && parent_donation.keeper_fee.is_none() {
+                    if let Some(interval) = parent_donation.payout_interval {
+                        NEXT_PAYOUT.save(deps.storage, &interval.after(&env.block))?;
+                    }
+
+                    resp = resp
+                        .add_submessages(donate_parent(deps.branch(), &env, &parent_donation, None)?)
+                        .add_attribute("donated_to_parent", parent_donation.address.to_string());
+                }
+            }
+
+            STATE.save(deps.storage, &state)?;
+            record_counter(deps.storage, &env, counter)?;
+
+            if let Some(matched) = match_donation(deps.branch(), &env, &credited, &kept)? {
+                resp = resp.add_attribute("matched", matched.amount.amount.to_string());
+                if let Some(donation_id) = refundable {
+                    lock_refund(deps.storage, std::slice::from_ref(&matched.amount))?;
+                    let mut donation = REFUNDABLE.load(deps.storage, donation_id)?;
+                    donation.matched = Some(matched);
+                    REFUNDABLE.save(deps.storage, donation_id, &donation)?;
+                }
+            }
+
+            if let Some(manager) = ROUND_MANAGER.may_load(deps.storage)? {
+                resp = resp.add_submessage(round_hook(deps.branch(), &manager, &credited, &kept)?);
+            }
+
+            let (events, msgs) = reach_milestones(deps.branch(), &env, previous, counter)?;
+            resp = resp.add_events(events).add_submessages(msgs);
+        }
+
+        if credited != info.sender {
+            resp = resp.add_attribute("beneficiary", credited.as_str());
+        }
+        resp = resp
+            .add_attribute("action", "donate")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("counter", counter.to_string());
+        
+        Ok(resp)
+    }
+
+    pub fn withdraw(deps: DepsMut, env: Env, info: MessageInfo, ibc: Option<IbcWithdraw>) -> Result<Response, ContractError> {
+        ensure_not_paused(deps.as_ref())?;
+        ensure_no_governance(deps.as_ref())?;
+        ensure_no_proposal_required(deps.as_ref())?;
+        ensure_role(deps.as_ref(), &info.sender, Role::Treasurer)?;
+
+        let recipient = withdraw_recipient(deps.as_ref(), &info.sender)?;
+        let beneficiaries = BENEFICIARIES.may_load(deps.storage)?.unwrap_or_default();
+        let default_timeout = ibc_timeout(deps.as_ref())?;
+        if let Some(ibc) = &ibc {
+            if !beneficiaries.is_empty() {
+                return Err(ContractError::IbcWithdrawWithBeneficiaries {});
+            }
+            if IBC_DESTINATIONS.may_load(deps.storage, &ibc.channel_id)?.as_ref() != Some(&ibc.remote_address) {
+                return Err(ContractError::UnauthorizedIbcDestination {
+                    channel_id: ibc.channel_id.clone(),
+                    remote_address: ibc.remote_address.clone(),
+                });
+            }
+        }
+        let funds = release_funds(deps, &env, None)?;
+
+        let mut resp = Response::new().add_attribute("action", "withdraw").add_attribute("sender", info.sender.as_str());
+        if funds.is_empty() {
+            return Ok(resp);
+        }
+
+        if let Some(ibc) = ibc {
+            let timeout = IbcTimeout::with_timestamp(env.block.time.plus_seconds(ibc.timeout.unwrap_or(default_timeout)));
+            resp = resp
+                .add_messages(funds.into_iter().map(|amount| IbcMsg::Transfer {
+                    channel_id: ibc.channel_id.clone(),
+                    to_address: ibc.remote_address.clone(),
+                    amount,
+                    timeout: timeout.clone(),
+                }))
+                .add_attribute("channel_id", ibc.channel_id)
+                .add_attribute("remote_address", ibc.remote_address);
+        } else if !beneficiaries.is_empty() {
+            resp = resp.add_messages(split_funds(&funds, &beneficiaries));
+        } else {
+            resp = resp.add_message(BankMsg::Send { to_address: recipient.to_string(), amount: funds });
+        }
+        Ok(resp)
+    }
+
+    pub fn create_campaign(
+        deps: DepsMut,
+        info: MessageInfo,
+        name: String,
+        minimal_donation: Coin,
+        owner: Option<String>,
+        parent: Option<CampaignParent>,
+    ) -> Result<Response, ContractError> {
+        ensure_not_paused(deps.as_ref())?;
+        ensure_role(deps.as_ref(), &info.sender, Role::ConfigManager)?;
+
+        if CAMPAIGNS.has(deps.storage, &name) {
+            return Err(ContractError::CampaignExists { name });
+        }
+
+        let owner = match owner {
+            Some(owner) => deps.api.addr_validate(&owner)?,
+            None => info.sender.clone(),
+        };
+        let parent = parent
+            .map(|parent| -> StdResult<_> {
+                Ok(CampaignParentDonation {
+                    address: deps.api.addr_validate(&parent.addr)?,
+                    donating_period: parent.donating_period.max(1),
+                    part: parent.part,
+                })
+            })
+            .transpose()?;
+
+        CAMPAIGNS.save(
+            deps.storage,
+            &name,
+            &Campaign { owner: owner.clone(), minimal_donation, parent, counter: 0, balance: vec![] },
+        )?;
+
+        let resp = Response::new()
+            .add_attribute("action", "create_campaign")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("campaign", name)
+            .add_attribute("owner", owner.as_str());
+        Ok(resp)
+    }
+
+    pub fn donate_campaign(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        name: String,
+        memo: Option<String>,
+        beneficiary: Option<String>,
+        referrer: Option<String>,
+    ) -> Result<Response, ContractError> {
+        ensure_not_paused(deps.as_ref())?;
+        ensure_memo(memo.as_deref())?;
+        let (credited, referrer) = donation_parties(deps.as_ref(), &info, beneficiary, referrer)?;
+
+        let mut campaign = CAMPAIGNS
+            .may_load(deps.storage, &name)?
+            .ok_or_else(|| ContractError::UnknownCampaign { name: name.clone() })?;
+        let mut resp = Response::default();
+
+        let mut counted = info.funds.iter().any(|coin| coin.denom == campaign.minimal_donation.denom && coin.amount >= campaign.minimal_donation.amount);
+        if counted && !rate_limit(deps.storage, &env, &info.sender)? {
+            counted = false;
+            resp = resp.add_attribute("rate_limited", info.sender.as_str());
+        }
+
+        let mut kept = info.funds.clone();
+        if let (true, Some(referrer)) = (counted, &referrer) {
+            let reward = referral_reward(deps.storage, referrer, &mut kept)?;
+            if !reward.is_empty() {
+                resp = resp
+                    .add_message(BankMsg::Send { to_address: referrer.to_string(), amount: reward })
+                    .add_attribute("referrer", referrer.as_str());
+            }
+        }
+
+        let mut campaigns_balance = CAMPAIGN_BALANCE.may_load(deps.storage)?.unwrap_or_default();
+        for coin in &kept {
+            add_coin(&mut campaign.balance, coin);
+            add_coin(&mut campaigns_balance, coin);
+        }
+
+        if counted {
+            campaign.counter = campaign.counter.checked_add(1).ok_or(ContractError::CounterOverflow {})?;
+            log_donation(deps.storage, &env, &credited, &info.funds, memo, Some(name.clone()))?;
+
+            if let Some(parent) = &campaign.parent {
+                if campaign.counter % parent.donating_period == 0 {
+                    let funds: Vec<Coin> = campaign.balance.iter_mut().map(|coin| {
+                        let share = coin.amount * parent.part;
+                        coin.amount -= share;
+                        Coin { denom: coin.denom.clone(), amount: share }
+                    }).filter(|coin| !coin.amount.is_zero()).collect();
+                    for coin in &funds {
+                        sub_coin(&mut campaigns_balance, coin);
+                    }
+
+                    resp = resp
+                        .add_message(WasmMsg::Execute {
+                            contract_addr: parent.address.to_string(),
+                            msg: to_json_binary(&ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None })?,
+                            funds,
+                        })
+                        .add_attribute("donated_to_parent", parent.address.to_string());
+                }
+            }
+        }
+        campaign.balance.retain(|coin| !coin.amount.is_zero());
+        CAMPAIGNS.save(deps.storage, &name, &campaign)?;
+        campaigns_balance.retain(|coin| !coin.amount.is_zero());
+        CAMPAIGN_BALANCE.save(deps.storage, &campaigns_balance)?;
+
+        resp = resp
+            .add_attribute("action", "donate")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("campaign", name)
+            .add_attribute("counter", campaign.counter.to_string());
+        Ok(resp)
+    }
+
+    fn donation_parties(deps: Deps, info: &MessageInfo, beneficiary: Option<String>, referrer: Option<String>) -> Result<(Addr, Option<Addr>), ContractError> {
+        let credited = match beneficiary {
+            Some(beneficiary) => deps.api.addr_validate(&beneficiary)?,
+            None => info.sender.clone(),
+        };
+
+        ensure_allowed(deps, &info.sender)?;
+        if credited != info.sender {
+            ensure_allowed(deps, &credited)?;
+        }
+
+        let referrer = referrer.map(|referrer| deps.api.addr_validate(&referrer)).transpose()?;
+        if let Some(referrer) = &referrer {
+            if *referrer == info.sender || *referrer == credited {
+                return Err(ContractError::SelfReferral {});
+            }
+            ensure_allowed(deps, referrer)?;
+        }
+
+        Ok((credited, referrer))
+    }
+
+    fn referral_reward(storage: &mut dyn Storage, referrer: &Addr, funds: &mut [Coin]) -> StdResult<Vec<Coin>> {
+        let part = REFERRAL_PART.may_load(storage)?.unwrap_or_default();
+        let reward: Vec<Coin> = funds.iter_mut().map(|coin| {
+            let amount = coin.amount * part;
+            coin.amount -= amount;
+            Coin { denom: coin.denom.clone(), amount }
+        }).filter(|coin| !coin.amount.is_zero()).collect();
+
+        for coin in &reward {
+            REFERRALS.update(storage, (referrer, &coin.denom), |total| -> StdResult<_> {
+                Ok(total.unwrap_or_default() + coin.amount)
+            })?;
+        }
+        Ok(reward)
+    }
+
+    fn unrefer_donation(storage: &mut dyn Storage, referral: &Referral) -> StdResult<()> {
+        for coin in &referral.reward {
+            let key = (&referral.referrer, coin.denom.as_str());
+            let total = REFERRALS.may_load(storage, key)?.unwrap_or_default().saturating_sub(coin.amount);
+            if total.is_zero() {
+                REFERRALS.remove(storage, key);
+            } else {
+                REFERRALS.save(storage, key, &total)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn ensure_memo(memo: Option<&str>) -> Result<(), ContractError> {
+        let len = memo.map_or(0, |memo| memo.chars().count());
+        if len > MAX_MEMO_LEN {
+            return Err(ContractError::MemoTooLong { max: MAX_MEMO_LEN, len });
+        }
+        Ok(())
+    }
+
+    fn log_donation(storage: &mut dyn Storage, env: &Env, donor: &Addr, funds: &[Coin], memo: Option<String>, campaign: Option<String>) -> StdResult<u64> {
+        let id = LAST_LOGGED_DONATION_ID.may_load(storage)?.unwrap_or_default() + 1;
+        LAST_LOGGED_DONATION_ID.save(storage, &id)?;
+        donation_log().save(
+            storage,
+            id,
+            &Donation {
+                donor: donor.clone(),
+                funds: funds.to_vec(),
+                memo,
+                campaign,
+                height: env.block.height,
+                time: env.block.time,
+            },
+        )?;
+        Ok(id)
+    }
+
+    pub fn withdraw_campaign(deps: DepsMut, env: Env, info: MessageInfo, ibc: Option<IbcWithdraw>, name: String) -> Result<Response, ContractError> {
+        ensure_not_paused(deps.as_ref())?;
+
+        let mut campaign = CAMPAIGNS
+            .may_load(deps.storage, &name)?
+            .ok_or_else(|| ContractError::UnknownCampaign { name: name.clone() })?;
+        if campaign.owner != info.sender {
+            return Err(ContractError::NotCampaignOwner { name });
+        }
+
+        let funds = std::mem::take(&mut campaign.balance);
+        CAMPAIGNS.save(deps.storage, &name, &campaign)?;
+        let mut campaigns_balance = CAMPAIGN_BALANCE.may_load(deps.storage)?.unwrap_or_default();
+        for coin in &funds {
+            sub_coin(&mut campaigns_balance, coin);
+        }
+        campaigns_balance.retain(|coin| !coin.amount.is_zero());
+        CAMPAIGN_BALANCE.save(deps.storage, &campaigns_balance)?;
+
+        let mut resp = Response::new()
+            .add_attribute("action", "withdraw")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("campaign", name);
+        if funds.is_empty() {
+            return Ok(resp);
+        }
+
+        if let Some(ibc) = ibc {
+            let timeout = IbcTimeout::with_timestamp(env.block.time.plus_seconds(ibc.timeout.unwrap_or(ibc_timeout(deps.as_ref())?)));
+            resp = resp
+                .add_messages(funds.into_iter().map(|amount| IbcMsg::Transfer {
+                    channel_id: ibc.channel_id.clone(),
+                    to_address: ibc.remote_address.clone(),
+                    amount,
+                    timeout: timeout.clone(),
+                }))
+                .add_attribute("channel_id", ibc.channel_id)
+                .add_attribute("remote_address", ibc.remote_address);
+        } else {
+            resp = resp.add_message(BankMsg::Send { to_address: info.sender.to_string(), amount: funds });
+        }
+        Ok(resp)
+    }
+
+    pub fn propose(deps: DepsMut, env: Env, info: MessageInfo, action: ProposalAction) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        let owners = OWNERS.load(deps.storage)?;
+
+        let action = match action {
+            ProposalAction::Withdraw { amount, recipient } => ProposalAction::Withdraw { amount, recipient: deps.api.addr_validate(&recipient)?.into() },
+            ProposalAction::GrantRole { address, role } => ProposalAction::GrantRole { address: deps.api.addr_validate(&address)?.into(), role },
+            ProposalAction::RevokeRole { address, role } => ProposalAction::RevokeRole { address: deps.api.addr_validate(&address)?.into(), role },
+            ProposalAction::UpdateWithdrawRecipient { recipient } => ProposalAction::UpdateWithdrawRecipient {
+                recipient: recipient.map(|recipient| deps.api.addr_validate(&recipient).map(String::from)).transpose()?,
+            },
+            ProposalAction::UpdateAccessList { list, add, remove } => ProposalAction::UpdateAccessList {
+                list,
+                add: add.iter().map(|address| deps.api.addr_validate(address).map(String::from)).collect::<StdResult<_>>()?,
+                remove: remove.iter().map(|address| deps.api.addr_validate(address).map(String::from)).collect::<StdResult<_>>()?,
+            },
+            action => action,
+        };
+
+        let id = LAST_PROPOSAL_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+        LAST_PROPOSAL_ID.save(deps.storage, &id)?;
+        PROPOSALS.save(
+            deps.storage,
+            id,
+            &Proposal {
+                proposer: info.sender.clone(),
+                action,
+                approvals: vec![info.sender.clone()],
+                expires: owners.proposal_duration.after(&env.block),
+                executed: false,
+            },
+        )?;
+
+        let resp = Response::new()
+            .add_attribute("action", "propose")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("proposal_id", id.to_string());
+        Ok(resp)
+    }
+
+    pub fn approve(deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+
+        let mut proposal = open_proposal(deps.as_ref(), &env, id)?;
+        if proposal.approvals.contains(&info.sender) {
+            return Err(ContractError::AlreadyApproved { proposal_id: id });
+        }
+
+        proposal.approvals.push(info.sender.clone());
+        PROPOSALS.save(deps.storage, id, &proposal)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "approve")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("proposal_id", id.to_string())
+            .add_attribute("approvals", proposal.approvals.len().to_string());
+        Ok(resp)
+    }
+
+    pub fn execute(mut deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+        ensure_not_paused(deps.as_ref())?;
+
+        let owners = OWNERS.load(deps.storage)?;
+
+        let mut proposal = open_proposal(deps.as_ref(), &env, id)?;
+        let approvals = proposal.approvals.iter().filter(|approval| owners.members.contains(approval)).count() as u64;
+        if approvals < owners.threshold {
+            return Err(ContractError::ThresholdNotReached { proposal_id: id });
+        }
+
+        proposal.executed = true;
+        PROPOSALS.save(deps.storage, id, &proposal)?;
+
+        let mut resp = Response::new()
+            .add_attribute("action", "execute")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("proposal_id", id.to_string());
+
+        match proposal.action {
+            ProposalAction::Withdraw { amount, recipient } => {
+                ensure_no_governance(deps.as_ref())?;
+                let funds = release_funds(deps.branch(), &env, Some(amount))?;
+                if !funds.is_empty() {
+                    resp = resp.add_message(BankMsg::Send { to_address: recipient, amount: funds });
+                }
+            }
+            ProposalAction::GrantRole { address, role } => set_role(deps.storage, &Addr::unchecked(address), role, true)?,
+            ProposalAction::RevokeRole { address, role } => set_role(deps.storage, &Addr::unchecked(address), role, false)?,
+            ProposalAction::UpdateWithdrawRecipient { recipient } => save_withdraw_recipient(deps.storage, recipient.map(Addr::unchecked))?,
+            ProposalAction::Delegate { validator, amount } => {
+                resp = resp.add_message(delegate_funds(deps.as_ref(), &env, validator, amount)?);
+            }
+            ProposalAction::Undelegate { validator, amount } => {
+                resp = resp.add_message(undelegate_funds(deps, &env, validator, amount)?);
+            }
+            ProposalAction::Redelegate { src_validator, dst_validator, amount } => {
+                resp = resp.add_message(StakingMsg::Redelegate { src_validator, dst_validator, amount });
+            }
+            ProposalAction::UpdateIbcDestination { channel_id, remote_address } => save_ibc_destination(deps.storage, &channel_id, remote_address)?,
+            ProposalAction::UpdateIbcCounterparty { connection_id, counterparty } => save_ibc_counterparty(deps.storage, &connection_id, counterparty)?,
+            ProposalAction::ClaimRewards {} => resp = resp.add_messages(reward_claims(deps.as_ref(), &env)?),
+            ProposalAction::UpdateAccessMode { mode } => ACCESS_MODE.save(deps.storage, &mode)?,
+            ProposalAction::UpdateAccessList { list, add, remove } => {
+                let add: Vec<_> = add.into_iter().map(Addr::unchecked).collect();
+                let remove: Vec<_> = remove.into_iter().map(Addr::unchecked).collect();
+                save_access_list(deps.storage, list, &add, &remove)?;
+            }
+            ProposalAction::RemoveMilestone { threshold } => delete_milestone(deps.storage, threshold)?,
+            ProposalAction::AddMilestones { milestones } => {
+                let counter = load_counter(deps.storage)?;
+                save_milestones(deps.storage, counter, milestones)?;
+            }
+        }
+        Ok(resp)
+    }
+
+    pub fn update_withdraw_recipient(deps: DepsMut, info: MessageInfo, recipient: Option<String>) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        ensure_no_proposal_required(deps.as_ref())?;
+
+        let recipient = recipient.map(|recipient| deps.api.addr_validate(&recipient)).transpose()?;
+        save_withdraw_recipient(deps.storage, recipient)?;
+
+        let resp = Response::new().add_attribute("action", "update_withdraw_recipient").add_attribute("sender", info.sender.as_str());
+        Ok(resp)
+    }
+
+    pub fn update_ibc_counterparty(deps: DepsMut, info: MessageInfo, connection_id: String, counterparty: Option<IbcCounterparty>) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        ensure_no_proposal_required(deps.as_ref())?;
+        save_ibc_counterparty(deps.storage, &connection_id, counterparty)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "update_ibc_counterparty")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("connection_id", connection_id);
+        Ok(resp)
+    }
+
+    pub fn settle_ibc_donation(mut deps: DepsMut, env: Env, info: MessageInfo, channel_id: String, packet_id: u64) -> Result<Response, ContractError> {
+        ensure_not_paused(deps.as_ref())?;
+
+        let mut donation = IBC_DONATIONS
+            .may_load(deps.storage, (&channel_id, packet_id))?
+            .ok_or_else(|| ContractError::UnknownIbcDonation { channel_id: channel_id.clone(), packet_id })?;
+
+        let amount_of = |coins: &[Coin], denom: &str| coins.iter().find(|coin| coin.denom == denom).map(|coin| coin.amount).unwrap_or_default();
+        for coin in &info.funds {
+            if amount_of(&donation.received, &coin.denom) + coin.amount > amount_of(&donation.funds, &coin.denom) {
+                return Err(ContractError::UnexpectedIbcFunds { denom: coin.denom.clone() });
+            }
+            add_coin(&mut donation.received, coin);
+        }
+
+        let missing = donation.funds.iter().find(|coin| amount_of(&donation.received, &coin.denom) < coin.amount);
+        if let Some(coin) = missing {
+            if info.funds.is_empty() {
+                return Err(ContractError::IbcFundsNotReceived { denom: coin.denom.clone() });
+            }
+            IBC_DONATIONS.save(deps.storage, (&channel_id, packet_id), &donation)?;
+
+            let resp = Response::new()
+                .add_attribute("action", "settle_ibc_donation")
+                .add_attribute("channel_id", channel_id)
+                .add_attribute("packet_id", packet_id.to_string())
+                .add_attribute("settled", "false");
+            return Ok(resp);
+        }
+        IBC_DONATIONS.remove(deps.storage, (&channel_id, packet_id));
+
+        let previous = load_counter(deps.storage)?;
+        let counter = previous.checked_add(donation.donations).ok_or(ContractError::CounterOverflow {})?;
+        record_counter(deps.storage, &env, counter)?;
+        let (events, msgs) = reach_milestones(deps.branch(), &env, previous, counter)?;
+
+        let resp = Response::new()
+            .add_events(events)
+            .add_submessages(msgs)
+            .add_attribute("action", "settle_ibc_donation")
+            .add_attribute("channel_id", channel_id)
+            .add_attribute("packet_id", packet_id.to_string())
+            .add_attribute("settled", "true")
+            .add_attribute("counter", counter.to_string());
+        Ok(resp)
+    }
+
+    pub fn update_ibc_destination(deps: DepsMut, info: MessageInfo, channel_id: String, remote_address: Option<String>) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        ensure_no_proposal_required(deps.as_ref())?;
+        save_ibc_destination(deps.storage, &channel_id, remote_address)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "update_ibc_destination")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("channel_id", channel_id);
+        Ok(resp)
+    }
+
+    pub fn propose_spend(deps: DepsMut, env: Env, info: MessageInfo, amount: Vec<Coin>, recipient: String, description: String) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        let governance = GOVERNANCE.may_load(deps.storage)?.ok_or(ContractError::GovernanceDisabled {})?;
+
+        let denom = STATE.load(deps.storage)?.minimal_donation.denom;
+        let total_weight = TOTAL_CONTRIBUTED.may_load_at_height(deps.storage, &denom, env.block.height)?.unwrap_or_default();
+
+        let id = LAST_SPENDING_PROPOSAL_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+        LAST_SPENDING_PROPOSAL_ID.save(deps.storage, &id)?;
+        SPENDING_PROPOSALS.save(
+            deps.storage,
+            id,
+            &SpendingProposal {
+                proposer: info.sender.clone(),
+                description,
+                amount,
+                recipient: deps.api.addr_validate(&recipient)?,
+                yes: Uint128::zero(),
+                no: Uint128::zero(),
+                abstain: Uint128::zero(),
+                total_weight,
+                height: env.block.height,
+                expires: governance.voting_period.after(&env.block),
+                executed: false,
+            },
+        )?;
+
+        let resp = Response::new()
+            .add_attribute("action", "propose_spend")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("proposal_id", id.to_string());
+        Ok(resp)
+    }
+
+    pub fn vote(deps: DepsMut, env: Env, info: MessageInfo, id: u64, vote: VoteOption) -> Result<Response, ContractError> {
+        let mut proposal = SPENDING_PROPOSALS.load(deps.storage, id)?;
+        if proposal.expires.is_expired(&env.block) {
+            return Err(ContractError::VotingClosed { proposal_id: id });
+        }
+
+        if VOTES.has(deps.storage, (id, &info.sender)) {
+            return Err(ContractError::AlreadyVoted { proposal_id: id });
+        }
+
+        let denom = STATE.load(deps.storage)?.minimal_donation.denom;
+        let weight = CONTRIBUTIONS.may_load_at_height(deps.storage, (&info.sender, &denom), proposal.height)?.unwrap_or_default();
+        if weight.is_zero() {
+            return Err(ContractError::NoVotingPower {});
+        }
+
+        match vote {
+            VoteOption::Yes => proposal.yes += weight,
+            VoteOption::No => proposal.no += weight,
+            VoteOption::Abstain => proposal.abstain += weight,
+        }
+        VOTES.save(deps.storage, (id, &info.sender), &vote)?;
+        SPENDING_PROPOSALS.save(deps.storage, id, &proposal)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "vote")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("proposal_id", id.to_string())
+            .add_attribute("weight", weight.to_string());
+        Ok(resp)
+    }
+
+    pub fn execute_spend(mut deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+        ensure_not_paused(deps.as_ref())?;
+
+        let governance = GOVERNANCE.may_load(deps.storage)?.ok_or(ContractError::GovernanceDisabled {})?;
+        let mut proposal = SPENDING_PROPOSALS.load(deps.storage, id)?;
+        if proposal.executed {
+            return Err(ContractError::ProposalExecuted { proposal_id: id });
+        }
+        if !proposal.expires.is_expired(&env.block) {
+            return Err(ContractError::VotingOpen { proposal_id: id });
+        }
+        if !proposal.passed(&governance) {
+            return Err(ContractError::ProposalRejected { proposal_id: id });
+        }
+
+        proposal.executed = true;
+        SPENDING_PROPOSALS.save(deps.storage, id, &proposal)?;
+
+        let funds = release_funds(deps.branch(), &env, Some(proposal.amount))?;
+
+        let mut resp = Response::new()
+            .add_attribute("action", "execute_spend")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("proposal_id", id.to_string());
+        if !funds.is_empty() {
+            resp = resp.add_message(BankMsg::Send { to_address: proposal.recipient.to_string(), amount: funds });
+        }
+        Ok(resp)
+    }
+
+    pub fn update_beneficiaries(deps: DepsMut, info: MessageInfo, beneficiaries: Vec<Beneficiary>) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::ConfigManager)?;
+
+        if beneficiaries.is_empty() {
+            BENEFICIARIES.remove(deps.storage);
+        } else {
+            let total = beneficiaries.iter().fold(Decimal::zero(), |total, beneficiary| total + beneficiary.weight);
+            if total != Decimal::one() {
+                return Err(ContractError::InvalidWeights { total });
+            }
+
+            let beneficiaries = beneficiaries
+                .into_iter()
+                .map(|beneficiary| {
+                    Ok(BeneficiaryShare {
+                        address: deps.api.addr_validate(&beneficiary.address)?,
+                        weight: beneficiary.weight,
+                    })
+                })
+                .collect::<StdResult<Vec<_>>>()?;
+            BENEFICIARIES.save(deps.storage, &beneficiaries)?;
+        }
+
+        let resp = Response::new().add_attribute("action", "update_beneficiaries").add_attribute("sender", info.sender.as_str());
+        Ok(resp)
+    }
+
+    pub fn update_withdraw_limits(deps: DepsMut, info: MessageInfo, limits: Option<WithdrawLimits>) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::ConfigManager)?;
+
+        match limits {
+            Some(limits) => WITHDRAW_LIMITS.save(deps.storage, &limits)?,
+            None => WITHDRAW_LIMITS.remove(deps.storage),
+        }
+        WITHDRAW_WINDOW.remove(deps.storage);
+
+        let resp = Response::new().add_attribute("action", "update_withdraw_limits").add_attribute("sender", info.sender.as_str());
+        Ok(resp)
+    }
+
+    pub fn grant_role(deps: DepsMut, info: MessageInfo, address: String, role: Role) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        ensure_no_proposal_required(deps.as_ref())?;
+        let address = deps.api.addr_validate(&address)?;
+        set_role(deps.storage, &address, role, true)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "grant_role")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("address", address.as_str());
+        Ok(resp)
+    }
+
+    pub fn revoke_role(deps: DepsMut, info: MessageInfo, address: String, role: Role) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        ensure_no_proposal_required(deps.as_ref())?;
+        let address = deps.api.addr_validate(&address)?;
+        set_role(deps.storage, &address, role, false)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "revoke_role")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("address", address.as_str());
+        Ok(resp)
+    }
+
+    pub fn set_paused(deps: DepsMut, info: MessageInfo, paused: bool) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Pauser)?;
+
+        PAUSED.save(deps.storage, &paused)?;
+
+        let resp = Response::new()
+            .add_attribute("action", if paused { "pause" } else { "unpause" })
+            .add_attribute("sender", info.sender.as_str());
+        Ok(resp)
+    }
+
+    pub fn reclaim(mut deps: DepsMut, env: Env, info: MessageInfo, donation_id: u64) -> Result<Response, ContractError> {
+        let donation = REFUNDABLE.load(deps.storage, donation_id)?;
+        if info.sender != donation.donor {
+            return Err(ContractError::NotDonor { donation_id });
+        }
+
+        if donation.expires.is_expired(&env.block) {
+            return Err(ContractError::RefundWindowExpired { donation_id });
+        }
+
+        REFUNDABLE.remove(deps.storage, donation_id);
+        unlock_refund(deps.storage, &donation.locked())?;
+        let credited = donation.beneficiary.as_ref().unwrap_or(&donation.donor);
+        remove_contribution(deps.storage, &env, credited, &donation.funds)?;
+        let unmatched = match &donation.matched {
+            Some(matched) => unmatch_donation(deps.branch(), credited, matched)?,
+            None => None,
+        };
+        if let Some(referral) = &donation.referral {
+            unrefer_donation(deps.storage, referral)?;
+        }
+
+        let round_hook = match ROUND_MANAGER.may_load(deps.storage)? {
+            Some(manager) if donation.counted => Some(round_unhook(deps.branch(), &manager, credited, &donation.funds)?),
+            _ => None,
+        };
+
+        let mut counter = load_counter(deps.storage)?;
+        if donation.counted {
+            counter = counter.saturating_sub(donation.count.max(1));
+            record_counter(deps.storage, &env, counter)?;
+        }
+
+        let bank_msg = BankMsg::Send { to_address: donation.donor.to_string(), amount: donation.funds };
+
+        let resp = Response::new()
+            .add_message(bank_msg)
+            .add_messages(unmatched)
+            .add_submessages(round_hook)
+            .add_attribute("action", "reclaim")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("donation_id", donation_id.to_string())
+            .add_attribute("counter", counter.to_string());
+        Ok(resp)
+    }
+
+    pub fn distribute(mut deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        ensure_not_paused(deps.as_ref())?;
+
+        let next_payout = NEXT_PAYOUT.may_load(deps.storage)?.unwrap_or(Expiration::Never {});
+        let pending = PAYOUT_PENDING.may_load(deps.storage)?.unwrap_or(false);
+        if !pending && !next_payout.is_expired(&env.block) {
+            return Err(ContractError::PayoutNotDue { next_payout });
+        }
+
+        let parent_donation = PARENT_DONATION.load(deps.storage)?;
+        PAYOUT_PENDING.remove(deps.storage);
+        if let Some(interval) = parent_donation.payout_interval {
+            NEXT_PAYOUT.save(deps.storage, &interval.after(&env.block))?;
+        }
+
+        let resp = Response::new()
+            .add_submessages(donate_parent(deps.branch(), &env, &parent_donation, Some(&info.sender))?)
+            .add_attribute("action", "distribute")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("donated_to_parent", parent_donation.address.to_string());
+        Ok(resp)
+    }
+
+    fn withdraw_recipient(deps: Deps, sender: &Addr) -> StdResult<Addr> {
+        if let Some(recipient) = WITHDRAW_RECIPIENT.may_load(deps.storage)? {
+            return Ok(recipient);
+        }
+
+        let owners = OWNERS.load(deps.storage)?;
+        if owners.members.contains(sender) {
+            return Ok(sender.clone());
+        }
+        Ok(owners.members[0].clone())
+    }
+
+    fn add_owner(storage: &mut dyn Storage, address: &Addr) -> StdResult<()> {
+        let mut owners = OWNERS.load(storage)?;
+        if !owners.members.contains(address) {
+            owners.members.push(address.clone());
+            owners.members.sort();
+            OWNERS.save(storage, &owners)?;
+        }
+        Ok(())
+    }
+
+    fn remove_owner(storage: &mut dyn Storage, address: &Addr) -> Result<(), ContractError> {
+        let mut owners = OWNERS.load(storage)?;
+        owners.members.retain(|member| member != address);
+        if owners.threshold > owners.members.len() as u64 {
+            return Err(ContractError::InvalidThreshold {
+                threshold: owners.threshold,
+                members: owners.members.len() as u64,
+            });
+        }
+        OWNERS.save(storage, &owners)?;
+        Ok(())
+    }
+
+    fn set_role(storage: &mut dyn Storage, address: &Addr, role: Role, granted: bool) -> Result<(), ContractError> {
+        if role == Role::Owner {
+            return match granted {
+                true => Ok(add_owner(storage, address)?),
+                false => remove_owner(storage, address),
+            };
+        }
+
+        let mut roles = ROLES.may_load(storage, address)?.unwrap_or_default();
+        roles.set(role, granted);
+        if roles == Roles::default() {
+            ROLES.remove(storage, address);
+        } else {
+            ROLES.save(storage, address, &roles)?;
+        }
+        Ok(())
+    }
+
+    fn save_withdraw_recipient(storage: &mut dyn Storage, recipient: Option<Addr>) -> StdResult<()> {
+        match recipient {
+            Some(recipient) => WITHDRAW_RECIPIENT.save(storage, &recipient),
+            None => {
+                WITHDRAW_RECIPIENT.remove(storage);
+                Ok(())
+            }
+        }
+    }
+
+    fn reward_claims(deps: Deps, env: &Env) -> StdResult<Vec<DistributionMsg>> {
+        let claims = deps.querier
+            .query_all_delegations(&env.contract.address)?
+            .into_iter()
+            .map(|delegation| DistributionMsg::WithdrawDelegatorReward { validator: delegation.validator })
+            .collect();
+        Ok(claims)
+    }
+
+    fn save_access_list(storage: &mut dyn Storage, list: AccessList, add: &[Addr], remove: &[Addr]) -> StdResult<()> {
+        let map = match list {
+            AccessList::Allowlist => ALLOWLIST,
+            AccessList::Blocklist => BLOCKLIST,
+        };
+        for address in add {
+            map.save(storage, address, &Empty {})?;
+        }
+        for address in remove {
+            map.remove(storage, address);
+        }
+        Ok(())
+    }
+
+    fn delete_milestone(storage: &mut dyn Storage, threshold: u64) -> Result<(), ContractError> {
+        if !MILESTONES.has(storage, threshold) {
+            return Err(ContractError::UnknownMilestone { threshold });
+        }
+        MILESTONES.remove(storage, threshold);
+        Ok(())
+    }
+
+    pub fn delegate(deps: DepsMut, env: Env, info: MessageInfo, validator: String, amount: Coin) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        ensure_no_proposal_required(deps.as_ref())?;
+
+        let resp = Response::new()
+            .add_message(delegate_funds(deps.as_ref(), &env, validator.clone(), amount.clone())?)
+            .add_attribute("action", "delegate")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("validator", validator)
+            .add_attribute("amount", amount.to_string());
+        Ok(resp)
+    }
+
+    pub fn undelegate(deps: DepsMut, env: Env, info: MessageInfo, validator: String, amount: Coin) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        ensure_no_proposal_required(deps.as_ref())?;
+
+        let resp = Response::new()
+            .add_message(undelegate_funds(deps, &env, validator.clone(), amount.clone())?)
+            .add_attribute("action", "undelegate")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("validator", validator)
+            .add_attribute("amount", amount.to_string());
+        Ok(resp)
+    }
+
+    pub fn redelegate(deps: DepsMut, info: MessageInfo, src_validator: String, dst_validator: String, amount: Coin) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        ensure_no_proposal_required(deps.as_ref())?;
+
+        let resp = Response::new()
+            .add_message(StakingMsg::Redelegate {
+                src_validator: src_validator.clone(),
+                dst_validator: dst_validator.clone(),
+                amount: amount.clone(),
+            })
+            .add_attribute("action", "redelegate")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("src_validator", src_validator)
+            .add_attribute("dst_validator", dst_validator)
+            .add_attribute("amount", amount.to_string());
+        Ok(resp)
+    }
+
+    pub fn claim_rewards(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        ensure_no_proposal_required(deps.as_ref())?;
+
+        let resp = Response::new()
+            .add_messages(reward_claims(deps.as_ref(), &env)?)
+            .add_attribute("action", "claim_rewards")
+            .add_attribute("sender", info.sender.as_str());
+        Ok(resp)
+    }
+
+    pub fn update_sinks(deps: DepsMut, info: MessageInfo, sinks: Vec<Sink>) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::ConfigManager)?;
+        save_sinks(deps.storage, sinks)?;
+
+        let resp = Response::new().add_attribute("action", "update_sinks").add_attribute("sender", info.sender.as_str());
+        Ok(resp)
+    }
+
+    pub fn update_counting_mode(deps: DepsMut, info: MessageInfo, mode: CountingMode) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::ConfigManager)?;
+        save_counting_mode(deps.storage, mode)?;
+
+        let resp = Response::new().add_attribute("action", "update_counting_mode").add_attribute("sender", info.sender.as_str());
+        Ok(resp)
+    }
+
+    pub fn update_referral_part(deps: DepsMut, info: MessageInfo, part: Decimal) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::ConfigManager)?;
+        save_referral_part(deps.storage, part)?;
+
+        let resp = Response::new().add_attribute("action", "update_referral_part").add_attribute("sender", info.sender.as_str());
+        Ok(resp)
+    }
+
+    pub fn update_access_mode(deps: DepsMut, info: MessageInfo, mode: AccessMode) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        ensure_no_proposal_required(deps.as_ref())?;
+        ACCESS_MODE.save(deps.storage, &mode)?;
+
+        let resp = Response::new().add_attribute("action", "update_access_mode").add_attribute("sender", info.sender.as_str());
+        Ok(resp)
+    }
+
+    pub fn update_access_list(deps: DepsMut, info: MessageInfo, list: AccessList, add: Vec<String>, remove: Vec<String>) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        ensure_no_proposal_required(deps.as_ref())?;
+
+        let added = add.iter().map(|address| deps.api.addr_validate(address)).collect::<StdResult<Vec<_>>>()?;
+        let removed = remove.iter().map(|address| deps.api.addr_validate(address)).collect::<StdResult<Vec<_>>>()?;
+        save_access_list(deps.storage, list, &added, &removed)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "update_access_list")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("added", add.len().to_string())
+            .add_attribute("removed", remove.len().to_string());
+        Ok(resp)
+    }
+
+    pub fn update_rate_limit(deps: DepsMut, info: MessageInfo, limit: Option<RateLimit>) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::ConfigManager)?;
+
+        match limit {
+            Some(limit) => RATE_LIMIT.save(deps.storage, &limit)?,
+            None => RATE_LIMIT.remove(deps.storage),
+        }
+
+        let resp = Response::new().add_attribute("action", "update_rate_limit").add_attribute("sender", info.sender.as_str());
+        Ok(resp)
+    }
+
+    pub fn add_milestones(deps: DepsMut, info: MessageInfo, milestones: Vec<Milestone>) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        if milestones.iter().any(|milestone| matches!(milestone.action, MilestoneAction::Message { .. })) {
+            ensure_no_proposal_required(deps.as_ref())?;
+        }
+
+        let counter = load_counter(deps.storage)?;
+        let added = milestones.len();
+        save_milestones(deps.storage, counter, milestones)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "add_milestones")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("added", added.to_string());
+        Ok(resp)
+    }
+
+    pub fn remove_milestone(deps: DepsMut, info: MessageInfo, threshold: u64) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::Owner)?;
+        ensure_no_proposal_required(deps.as_ref())?;
+        delete_milestone(deps.storage, threshold)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "remove_milestone")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("threshold", threshold.to_string());
+        Ok(resp)
+    }
+
+    pub fn update_voucher_key(deps: DepsMut, info: MessageInfo, key: Option<VoucherKey>) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::ConfigManager)?;
+
+        match key {
+            Some(key) => VOUCHER_KEY.save(deps.storage, &key)?,
+            None => VOUCHER_KEY.remove(deps.storage),
+        }
+
+        let resp = Response::new().add_attribute("action", "update_voucher_key").add_attribute("sender", info.sender.as_str());
+        Ok(resp)
+    }
+
+    pub fn redeem_voucher(mut deps: DepsMut, env: Env, info: MessageInfo, voucher: Voucher, signature: Binary) -> Result<Response, ContractError> {
+        ensure_not_paused(deps.as_ref())?;
+
+        let nonce = voucher.nonce;
+        let key = VOUCHER_KEY.may_load(deps.storage)?.ok_or(ContractError::VoucherKeyNotSet {})?;
+        if voucher.expiry.is_expired(&env.block) {
+            return Err(ContractError::VoucherExpired { nonce });
+        }
+        if REDEEMED_VOUCHERS.has(deps.storage, nonce) {
+            return Err(ContractError::VoucherRedeemed { nonce });
+        }
+
+        let message = to_json_vec(&(&env.contract.address, &env.block.chain_id, &voucher))?;
+        let verified = match key {
+            VoucherKey::Secp256k1(public_key) => deps.api.secp256k1_verify(&Sha256::digest(&message), &signature, &public_key),
+            VoucherKey::Ed25519(public_key) => deps.api.ed25519_verify(&message, &signature, &public_key),
+        };
+        if !verified.unwrap_or(false) {
+            return Err(ContractError::InvalidVoucherSignature { nonce });
+        }
+
+        let donor = deps.api.addr_validate(&voucher.donor)?;
+        ensure_allowed(deps.as_ref(), &donor)?;
+
+        let state = STATE.load(deps.storage)?;
+        if voucher.amount.denom != state.minimal_donation.denom || voucher.amount.amount < state.minimal_donation.amount {
+            return Err(ContractError::VoucherBelowMinimum { nonce, minimal_donation: state.minimal_donation });
+        }
+        REDEEMED_VOUCHERS.save(deps.storage, nonce, &donor)?;
+
+        let previous = load_counter(deps.storage)?;
+        let counter = previous.checked_add(1).ok_or(ContractError::CounterOverflow {})?;
+        record_counter(deps.storage, &env, counter)?;
+        let (events, msgs) = reach_milestones(deps.branch(), &env, previous, counter)?;
+
+        let resp = Response::new()
+            .add_events(events)
+            .add_submessages(msgs)
+            .add_attribute("action", "redeem_voucher")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("donor", donor.as_str())
+            .add_attribute("amount", voucher.amount.to_string())
+            .add_attribute("nonce", nonce.to_string())
+            .add_attribute("counter", counter.to_string());
+        Ok(resp)
+    }
+
+    pub fn fund_matching_pool(deps: DepsMut, env: Env, info: MessageInfo, ratio: Decimal, donor_cap: Option<Uint128>, deadline: Expiration) -> Result<Response, ContractError> {
+        let mut resp = Response::new();
+        if let Some(pool) = MATCHING_POOL.may_load(deps.storage)? {
+            if !pool.remaining.amount.is_zero() {
+                if !pool.deadline.is_expired(&env.block) {
+                    return Err(ContractError::MatchingPoolExists {});
+                }
+                resp = resp
+                    .add_message(BankMsg::Send { to_address: pool.sponsor.to_string(), amount: vec![pool.remaining.clone()] })
+                    .add_attribute("refunded", pool.remaining.to_string());
+            }
+        }
+
+        let deposit = match info.funds.as_slice() {
+            [deposit] if !deposit.amount.is_zero() && !deadline.is_expired(&env.block) => deposit.clone(),
+            _ => return Err(ContractError::InvalidMatchingPool {}),
+        };
+
+        let id = LAST_MATCHING_POOL_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+        LAST_MATCHING_POOL_ID.save(deps.storage, &id)?;
+        MATCHING_POOL.save(
+            deps.storage,
+            &MatchingPool {
+                id,
+                sponsor: info.sender.clone(),
+                remaining: deposit.clone(),
+                matched: Uint128::zero(),
+                ratio,
+                donor_cap,
+                deadline,
+            },
+        )?;
+
+        let resp = resp
+            .add_attribute("action", "fund_matching_pool")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("amount", deposit.to_string());
+        Ok(resp)
+    }
+
+    pub fn reclaim_matching_pool(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        let pool = MATCHING_POOL.may_load(deps.storage)?.ok_or(ContractError::NoMatchingPool {})?;
+        if pool.sponsor != info.sender {
+            return Err(ContractError::NotSponsor {});
+        }
+        if !pool.deadline.is_expired(&env.block) {
+            return Err(ContractError::MatchingPoolActive { deadline: pool.deadline });
+        }
+
+        MATCHING_POOL.remove(deps.storage);
+
+        let mut resp = Response::new()
+            .add_attribute("action", "reclaim_matching_pool")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("amount", pool.remaining.to_string());
+        if !pool.remaining.amount.is_zero() {
+            resp = resp.add_message(BankMsg::Send { to_address: info.sender.to_string(), amount: vec![pool.remaining] });
+        }
+        Ok(resp)
+    }
+
+    pub fn update_round_manager(deps: DepsMut, info: MessageInfo, manager: Option<String>) -> Result<Response, ContractError> {
+        ensure_role(deps.as_ref(), &info.sender, Role::ConfigManager)?;
+
+        ROUND_CONTRIBUTIONS.clear(deps.storage);
+        match manager {
+            Some(manager) => ROUND_MANAGER.save(deps.storage, &deps.api.addr_validate(&manager)?)?,
+            None => ROUND_MANAGER.remove(deps.storage),
+        }
+
+        let resp = Response::new().add_attribute("action", "update_round_manager").add_attribute("sender", info.sender.as_str());
+        Ok(resp)
+    }
+
+    fn reach_milestones(mut deps: DepsMut, env: &Env, previous: u64, counter: u64) -> Result<(Vec<Event>, Vec<SubMsg>), ContractError> {
+        let crossed = MILESTONES
+            .range(deps.storage, Some(Bound::exclusive(previous)), Some(Bound::inclusive(counter)), Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+
+        let mut events = vec![];
+        let mut msgs = vec![];
+        for (threshold, mut milestone) in crossed {
+            if milestone.reached_at.is_some() {
+                continue;
+            }
+            milestone.reached_at = Some(env.block.height);
+            MILESTONES.save(deps.storage, threshold, &milestone)?;
+
+            let mut event = Event::new("milestone")
+                .add_attribute("threshold", threshold.to_string())
+                .add_attribute("counter", counter.to_string());
+            match milestone.action {
+                MilestoneAction::Event {} => {}
+                MilestoneAction::NotifyParent {} => {
+                    if let Some(parent_donation) = PARENT_DONATION.may_load(deps.storage)? {
+                        msgs.extend(donate_parent(deps.branch(), env, &parent_donation, None)?);
+                    }
+                }
+                MilestoneAction::Message { msg } => {
+                    let funds = milestone_funds(&msg);
+                    let released = match ensure_no_governance(deps.as_ref()) {
+                        Ok(()) if !funds.is_empty() => release_funds(deps.branch(), env, Some(funds)).map(|_| ()),
+                        result => result,
+                    };
+                    match released {
+                        Ok(()) => msgs.push(SubMsg::reply_on_error(msg, MILESTONE_REPLY_FLAG | threshold)),
+                        Err(err) => event = event.add_attribute("skipped", err.to_string()),
+                    }
+                }
+            }
+            events.push(event);
+        }
+        Ok((events, msgs))
+    }
+
+    fn round_hook(deps: DepsMut, manager: &Addr, donor: &Addr, funds: &[Coin]) -> StdResult<SubMsg> {
+        for coin in funds {
+            ROUND_CONTRIBUTIONS.update(deps.storage, (donor, &coin.denom), |total| -> StdResult<_> {
+                Ok(total.unwrap_or_default() + coin.amount)
+            })?;
+        }
+        round_totals(deps.as_ref(), manager, donor)
+    }
+
+    fn round_unhook(deps: DepsMut, manager: &Addr, donor: &Addr, funds: &[Coin]) -> StdResult<SubMsg> {
+        for coin in funds {
+            ROUND_CONTRIBUTIONS.update(deps.storage, (donor, &coin.denom), |total| -> StdResult<_> {
+                Ok(total.unwrap_or_default().saturating_sub(coin.amount))
+            })?;
+        }
+        round_totals(deps.as_ref(), manager, donor)
+    }
+
+    fn round_totals(deps: Deps, manager: &Addr, donor: &Addr) -> StdResult<SubMsg> {
+        let totals = ROUND_CONTRIBUTIONS
+            .prefix(donor)
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|item| item.map(|(denom, amount)| Coin { denom, amount }))
+            .collect::<StdResult<_>>()?;
+
+        let msg = WasmMsg::Execute {
+            contract_addr: manager.to_string(),
+            msg: to_json_binary(&RoundHookMsg::DonationHook { donor: donor.to_string(), totals })?,
+            funds: vec![],
+        };
+        Ok(SubMsg::reply_on_error(msg, ROUND_HOOK_REPLY_ID))
+    }
+
+    fn match_donation(deps: DepsMut, env: &Env, donor: &Addr, funds: &[Coin]) -> StdResult<Option<MatchedDonation>> {
+        let mut pool = match MATCHING_POOL.may_load(deps.storage)? {
+            Some(pool) if !pool.deadline.is_expired(&env.block) && !pool.remaining.amount.is_zero() => pool,
+            _ => return Ok(None),
+        };
+
+        let donated = funds.iter().find(|coin| coin.denom == pool.remaining.denom).map(|coin| coin.amount).unwrap_or_default();
+        let mut matched = (donated * pool.ratio).min(pool.remaining.amount);
+
+        let donor_matched = MATCHED.may_load(deps.storage, (pool.id, donor))?.unwrap_or_default();
+        if let Some(donor_cap) = pool.donor_cap {
+            matched = matched.min(donor_cap.saturating_sub(donor_matched));
+        }
+        if matched.is_zero() {
+            return Ok(None);
+        }
+
+        MATCHED.save(deps.storage, (pool.id, donor), &(donor_matched + matched))?;
+        pool.remaining.amount -= matched;
+        pool.matched += matched;
+        MATCHING_POOL.save(deps.storage, &pool)?;
+
+        Ok(Some(MatchedDonation {
+            pool_id: pool.id,
+            sponsor: pool.sponsor,
+            amount: Coin { denom: pool.remaining.denom, amount: matched },
+        }))
+    }
+
+    fn unmatch_donation(deps: DepsMut, donor: &Addr, matched: &MatchedDonation) -> StdResult<Option<BankMsg>> {
+        let mut pool = match MATCHING_POOL.may_load(deps.storage)? {
+            Some(pool) if pool.id == matched.pool_id => pool,
+            _ => return Ok(Some(BankMsg::Send { to_address: matched.sponsor.to_string(), amount: vec![matched.amount.clone()] })),
+        };
+
+        MATCHED.update(deps.storage, (pool.id, donor), |donor_matched| -> StdResult<_> {
+            Ok(donor_matched.unwrap_or_default().saturating_sub(matched.amount.amount))
+        })?;
+        pool.remaining.amount += matched.amount.amount;
+        pool.matched = pool.matched.saturating_sub(matched.amount.amount);
+        MATCHING_POOL.save(deps.storage, &pool)?;
+
+        Ok(None)
+    }
+
+    fn sink_funds(sink: &Sink, funds: &[Coin]) -> Vec<Coin> {
+        funds
+            .iter()
+            .map(|coin| Coin { denom: coin.denom.clone(), amount: coin.amount * sink.part() })
+            .filter(|coin| !coin.amount.is_zero())
+            .collect()
+    }
+
+    fn split_funds(funds: &[Coin], beneficiaries: &[BeneficiaryShare]) -> Vec<BankMsg> {
+        let mut shares: Vec<Vec<Coin>> = vec![vec![]; beneficiaries.len()];
+
+        for coin in funds {
+            let mut remaining = coin.amount;
+            for (share, beneficiary) in shares.iter_mut().zip(beneficiaries) {
+                let amount = coin.amount * beneficiary.weight;
+                remaining -= amount;
+                share.push(Coin { denom: coin.denom.clone(), amount });
+            }
+
+            if let Some(first) = shares[0].last_mut() {
+                first.amount += remaining;
+            }
+        }
+
+        shares
+            .into_iter()
+            .zip(beneficiaries)
+            .filter_map(|(share, beneficiary)| {
+                let amount: Vec<Coin> = share.into_iter().filter(|coin| !coin.amount.is_zero()).collect();
+                (!amount.is_empty()).then(|| BankMsg::Send { to_address: beneficiary.address.to_string(), amount })
+            })
+            .collect()
+    }
+
+    fn open_proposal(deps: Deps, env: &Env, id: u64) -> Result<Proposal, ContractError> {
+        let proposal = PROPOSALS.load(deps.storage, id)?;
+        if proposal.executed {
+            return Err(ContractError::ProposalExecuted { proposal_id: id });
+        }
+        if proposal.expires.is_expired(&env.block) {
+            return Err(ContractError::ProposalExpired { proposal_id: id });
+        }
+        Ok(proposal)
+    }
+
+    fn release_funds(deps: DepsMut, env: &Env, requested: Option<Vec<Coin>>) -> Result<Vec<Coin>, ContractError> {
+        release_expired_refunds(deps.storage, env)?;
+
+        let liquid = available_balance(deps.as_ref(), env)?;
+        let withdrawable: Vec<Coin> = vesting_status(deps.as_ref(), env)?
+            .into_iter()
+            .map(|status| {
+                let liquid = liquid.iter().find(|coin| coin.denom == status.denom).map(|coin| coin.amount).unwrap_or_default();
+                Coin { amount: status.vested.saturating_sub(status.withdrawn).min(liquid), denom: status.denom }
+            })
+            .filter(|coin| !coin.amount.is_zero())
+            .collect();
+
+        let capped = requested.is_none();
+        let mut funds = match requested {
+            Some(requested) => {
+                for coin in &requested {
+                    let available = withdrawable.iter().find(|available| available.denom == coin.denom).map(|available| available.amount).unwrap_or_default();
+                    if coin.amount > available {
+                        return Err(ContractError::InsufficientFunds {
+                            requested: coin.clone(),
+                            available: Coin { denom: coin.denom.clone(), amount: available },
+                        });
+                    }
+                }
+                requested
+            }
+            None => withdrawable,
+        };
+
+        if let Some(limits) = WITHDRAW_LIMITS.may_load(deps.storage)? {
+            let mut window = withdraw_window(deps.as_ref(), env, &limits)?;
+
+            if let Some(last_withdraw) = window.last_withdraw {
+                let available_at = last_withdraw.plus_seconds(limits.cooldown);
+                if env.block.time < available_at {
+                    return Err(ContractError::WithdrawLimitExceeded { available_at });
+                }
+            }
+
+            let window_end = window.start.plus_seconds(limits.window);
+            let requested = !funds.is_empty();
+            let remaining = remaining_allowance(&limits, &window);
+            for coin in &mut funds {
+                if let Some(allowance) = remaining.iter().find(|allowance| allowance.denom == coin.denom) {
+                    if !capped && coin.amount > allowance.amount {
+                        return Err(ContractError::WithdrawLimitExceeded { available_at: window_end });
+                    }
+                    coin.amount = coin.amount.min(allowance.amount);
+                }
+            }
+            funds.retain(|coin| !coin.amount.is_zero());
+
+            if requested && funds.is_empty() {
+                return Err(ContractError::WithdrawLimitExceeded { available_at: window_end });
+            }
+
+            if !funds.is_empty() {
+                for coin in &funds {
+                    add_coin(&mut window.withdrawn, coin);
+                }
+                window.last_withdraw = Some(env.block.time);
+                WITHDRAW_WINDOW.save(deps.storage, &window)?;
+            }
+        }
+
+        funds.retain(|coin| !coin.amount.is_zero());
+        for coin in &funds {
+            WITHDRAWN.update(deps.storage, &coin.denom, |withdrawn| -> StdResult<_> {
+                Ok(withdrawn.unwrap_or_default() + coin.amount)
+            })?;
+        }
+
+        Ok(funds)
+    }
+
+    fn donate_parent(deps: DepsMut, env: &Env, parent_donation: &ParentDonation, keeper: Option<&Addr>) -> StdResult<Vec<SubMsg>> {
+        let mut funds: Vec<Coin> = available_balance(deps.as_ref(), env)?.into_iter().map(|mut coin| {
+            coin.amount = coin.amount * parent_donation.part;
+            coin
+        }).collect();
+
+        let mut msgs = vec![];
+
+        if let (Some(keeper), Some(keeper_fee)) = (keeper, parent_donation.keeper_fee) {
+            let fee: Vec<Coin> = funds.iter_mut().map(|coin| {
+                let fee = coin.amount * keeper_fee;
+                coin.amount -= fee;
+                Coin { denom: coin.denom.clone(), amount: fee }
+            }).filter(|coin| !coin.amount.is_zero()).collect();
+
+            if !fee.is_empty() {
+                msgs.push(SubMsg::new(BankMsg::Send { to_address: keeper.to_string(), amount: fee }));
+            }
+        }
+
+        let funds: Vec<Coin> = funds.into_iter().filter(|coin| !coin.amount.is_zero()).collect();
+
+        match &parent_donation.ibc {
+            Some(ibc) => {
+                let id = LAST_PACKET_ID.may_load(deps.storage)?.unwrap_or_default() + 1;
+                LAST_PACKET_ID.save(deps.storage, &id)?;
+                PENDING_SHARES.save(
+                    deps.storage,
+                    id,
+                    &PendingShare {
+                        transfer_channel_id: ibc.transfer_channel_id.clone(),
+                        remote_address: parent_donation.address.to_string(),
+                        funds: funds.clone(),
+                    },
+                )?;
+
+                let timeout = env.block.time.plus_seconds(ibc_timeout(deps.as_ref())?);
+                msgs.push(SubMsg::new(IbcMsg::SendPacket {
+                    channel_id: ibc.channel_id.clone(),
+                    data: to_json_binary(&CountingPacket::V1 { id, donations: 1, funds })?,
+                    timeout: IbcTimeout::with_timestamp(timeout),
+                }));
+            }
+            None => msgs.push(SubMsg::reply_on_error(
+                WasmMsg::Execute {
+                    contract_addr: parent_donation.address.to_string(),
+                    msg: to_json_binary(&ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None })?,
+                    funds,
+                },
+                PARENT_DONATION_REPLY_ID,
+            )),
+        }
+
+        Ok(msgs)
+    }
+}
+
+pub mod ibc {
+    use cosmwasm_std::{Coin, Deps, DepsMut, Env, IbcBasicResponse, IbcChannel, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse, IbcMsg, IbcOrder, IbcPacket, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, IbcTimeout, from_json, to_json_binary};
+
+    use crate::error::ContractError;
+    use crate::msg::{CountingAck, CountingPacket};
+    use crate::state::{PENDING_SHARES, IBC_COUNTERPARTIES, IBC_CHANNELS, IBC_DONATIONS, IbcDonation};
+
+    use super::{ensure_not_paused, ibc_denom, ibc_timeout, load_counter};
+
+    pub const IBC_VERSION: &str = "counting-1";
+
+    fn ensure_channel(deps: Deps, channel: &IbcChannel, counterparty_version: Option<&str>) -> Result<(), ContractError> {
+        if channel.order != IbcOrder::Unordered {
+            return Err(ContractError::InvalidIbcOrder {});
+        }
+
+        for version in [Some(channel.version.as_str()), counterparty_version].into_iter().flatten() {
+            if version != IBC_VERSION {
+                return Err(ContractError::InvalidIbcVersion { expected: IBC_VERSION.to_owned(), version: version.to_owned() });
+            }
+        }
+
+        let trusted = IBC_COUNTERPARTIES
+            .may_load(deps.storage, &channel.connection_id)?
+            .map(|counterparty| counterparty.port_id == channel.counterparty_endpoint.port_id)
+            .unwrap_or_default();
+        if !trusted {
+            return Err(ContractError::UntrustedIbcConnection { connection_id: channel.connection_id.clone() });
+        }
+        Ok(())
+    }
+
+    pub fn channel_open(deps: DepsMut, msg: IbcChannelOpenMsg) -> Result<IbcChannelOpenResponse, ContractError> {
+        ensure_channel(deps.as_ref(), msg.channel(), msg.counterparty_version())?;
+        Ok(None)
+    }
+
+    pub fn channel_connect(deps: DepsMut, msg: IbcChannelConnectMsg) -> Result<IbcBasicResponse, ContractError> {
+        let channel = msg.channel();
+        ensure_channel(deps.as_ref(), channel, msg.counterparty_version())?;
+        IBC_CHANNELS.save(deps.storage, &channel.endpoint.channel_id, &channel.connection_id)?;
+
+        let resp = IbcBasicResponse::new()
+            .add_attribute("action", "ibc_connect")
+            .add_attribute("channel_id", channel.endpoint.channel_id.as_str());
+        Ok(resp)
+    }
+
+    pub fn channel_close(deps: DepsMut, msg: IbcChannelCloseMsg) -> Result<IbcBasicResponse, ContractError> {
+        IBC_CHANNELS.remove(deps.storage, &msg.channel().endpoint.channel_id);
+
+        let resp = IbcBasicResponse::new()
+            .add_attribute("action", "ibc_close")
+            .add_attribute("channel_id", msg.channel().endpoint.channel_id.as_str());
+        Ok(resp)
+    }
+
+    pub fn packet_receive(deps: DepsMut, msg: IbcPacketReceiveMsg) -> Result<IbcReceiveResponse, ContractError> {
+        let resp = IbcReceiveResponse::new().add_attribute("action", "ibc_receive");
+        let resp = match receive_donations(deps, &msg.packet) {
+            Ok(counter) => resp
+                .set_ack(to_json_binary(&CountingAck::Result(to_json_binary(&counter)?))?)
+                .add_attribute("counter", counter.to_string()),
+            Err(err) => resp
+                .set_ack(to_json_binary(&CountingAck::Error(err.to_string()))?)
+                .add_attribute("error", err.to_string()),
+        };
+        Ok(resp)
+    }
+
+    pub fn packet_ack(deps: DepsMut, env: Env, msg: IbcPacketAckMsg) -> Result<IbcBasicResponse, ContractError> {
+        let CountingPacket::V1 { id, .. } = from_json(msg.original_packet.data)?;
+        let share = match PENDING_SHARES.may_load(deps.storage, id)? {
+            Some(share) => share,
+            None => return Ok(IbcBasicResponse::new().add_attribute("action", "ibc_ack")),
+        };
+        PENDING_SHARES.remove(deps.storage, id);
+
+        let resp = IbcBasicResponse::new()
+            .add_attribute("action", "ibc_ack")
+            .add_attribute("packet_id", id.to_string());
+
+        let resp = match from_json(msg.acknowledgement.data) {
+            Ok(CountingAck::Result(_)) => {
+                let timeout = IbcTimeout::with_timestamp(env.block.time.plus_seconds(ibc_timeout(deps.as_ref())?));
+                resp.add_messages(share.funds.into_iter().map(|amount| IbcMsg::Transfer {
+                    channel_id: share.transfer_channel_id.clone(),
+                    to_address: share.remote_address.clone(),
+                    amount,
+                    timeout: timeout.clone(),
+                }))
+            }
+            Ok(CountingAck::Error(err)) => resp.add_attribute("refunded", "true").add_attribute("error", err),
+            Err(err) => resp.add_attribute("refunded", "true").add_attribute("error", err.to_string()),
+        };
+        Ok(resp)
+    }
+
+    pub fn packet_timeout(deps: DepsMut, msg: IbcPacketTimeoutMsg) -> Result<IbcBasicResponse, ContractError> {
+        let CountingPacket::V1 { id, .. } = from_json(msg.packet.data)?;
+        PENDING_SHARES.remove(deps.storage, id);
+
+        let resp = IbcBasicResponse::new()
+            .add_attribute("action", "ibc_timeout")
+            .add_attribute("packet_id", id.to_string())
+            .add_attribute("refunded", "true");
+        Ok(resp)
+    }
+
+    fn receive_donations(deps: DepsMut, packet: &IbcPacket) -> Result<u64, ContractError> {
+        ensure_not_paused(deps.as_ref())?;
+
+        let channel_id = packet.dest.channel_id.as_str();
+        let connection_id = IBC_CHANNELS
+            .may_load(deps.storage, channel_id)?
+            .ok_or_else(|| ContractError::UntrustedIbcChannel { channel_id: channel_id.to_owned() })?;
+        let counterparty = IBC_COUNTERPARTIES
+            .may_load(deps.storage, &connection_id)?
+            .ok_or(ContractError::UntrustedIbcConnection { connection_id })?;
+
+        let CountingPacket::V1 { id, donations, funds } = from_json(&packet.data)?;
+        if IBC_DONATIONS.has(deps.storage, (channel_id, id)) {
+            return Err(ContractError::IbcDonationExists { channel_id: channel_id.to_owned(), packet_id: id });
+        }
+
+        let funds = funds
+            .into_iter()
+            .map(|coin| Coin { denom: ibc_denom(&counterparty.transfer_channel_id, &coin.denom), amount: coin.amount })
+            .collect();
+        IBC_DONATIONS.save(deps.storage, (channel_id, id), &IbcDonation { donations, funds, received: vec![] })?;
+        Ok(load_counter(deps.storage)?)
+    }
+}
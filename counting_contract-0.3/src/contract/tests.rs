@@ -0,0 +1,1085 @@
+use cosmwasm_std::{Addr, BankMsg, Binary, Coin, Decimal, Deps, DepsMut, Env, Event, IbcAcknowledgement, IbcMsg, IbcOrder, IbcTimeout, Reply, SubMsg, SubMsgResult, Uint128, WasmMsg, coins, from_json, to_json_binary};
+use cosmwasm_std::testing::{mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::testing::{mock_ibc_channel_open_try, mock_ibc_packet_ack, mock_ibc_packet_recv, mock_ibc_packet_timeout, mock_ibc_channel_connect_ack};
+use cw2::set_contract_version;
+use cw_storage_plus::Item;
+use cw_utils::{Duration, Expiration};
+use serde::{Deserialize, Serialize};
+
+use crate::contract::ibc::IBC_VERSION;
+use crate::contract::{ibc_denom, MILESTONE_REPLY_FLAG, PARENT_DONATION_REPLY_ID};
+use crate::error::ContractError;
+use crate::msg::{AccessList, AccessListResp, AccessMode, Beneficiary, ChangelogResp, ContributionsResp, CounterChange, CountingAck, CountingMode, CountingPacket, DonationResp, DonationsResp, ExecMsg, IbcCounterparty, IbcParent, IbcWithdraw, InstantiateMsg};
+use crate::msg::{DonationWindow, Governance, LeaderboardEntry, LeaderboardResp, LimitAction, MigrateMsg, Milestone, MilestoneAction, MilestonesResp, ProposalAction, RateLimit, Parent, QueryMsg, ReferralsResp, Role, ValueResp, Voucher, VoucherKey, VoucherResp};
+use crate::state::{State, STATE};
+use crate::{execute, ibc_channel_connect, ibc_channel_open, ibc_packet_ack, ibc_packet_receive, ibc_packet_timeout, instantiate, migrate, query, reply};
+
+const ATOM: &str = "atom";
+const OSMO: &str = "osmo";
+
+const ED25519_KEY: &str = "ebVWLo/mVPlAeLES6KmLp5AfhTrmlb7X4OORC60ElmQ=";
+const ED25519_SIGNATURE: &str = "v3S7fUe85bnAF7Zx/t5+nC+LUs10EaRlqiNjfr3NQ/vc/vrl9mdbk+X+GUlbhS/REa92XBdpYkoEY2BnTCwnAg==";
+const ED25519_LOW_SIGNATURE: &str = "joe2sLml2507DJc3xpKjtQOf0f4S9a/vy2lHKQ2GOaxp6O2LDzBFrdjioADKjmA7N4LJzmsxZj1c4rS+4t4uCQ==";
+const SECP256K1_KEY: &str = "A/lzoLhwYsOJ0SXYGZ6AO4Mrasa/eGek9s2HUGBg/ExY";
+const SECP256K1_SIGNATURE: &str = "/vHuhD4yjtHlyDLXDx3lcIXge2HLKwiahhO05jbrDetgVo9sxRt9inPDRbqtdRwCMM/cBclodhMzgj86UI9ZlQ==";
+
+fn voucher(nonce: u64) -> Voucher {
+    Voucher {
+        donor: "donor".to_owned(),
+        amount: Coin::new(10, ATOM),
+        nonce,
+        expiry: Expiration::AtHeight(12_400),
+    }
+}
+
+#[test]
+fn withdraw_over_ibc() {
+    let mut deps = mock_dependencies_with_balance(&[Coin::new(100, ATOM), Coin::new(50, OSMO)]);
+    let env = mock_env();
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            ibc_timeout: Some(300),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let withdraw = ExecMsg::Withdraw {
+        ibc: Some(IbcWithdraw {
+            channel_id: "channel-0".to_owned(),
+            remote_address: "remote".to_owned(),
+            timeout: None,
+        }),
+        campaign: None,
+    };
+
+    let err = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), withdraw.clone()).unwrap_err();
+    assert_eq!(err, ContractError::UnauthorizedIbcDestination { channel_id: "channel-0".to_owned(), remote_address: "remote".to_owned() });
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("treasurer", &[]),
+        ExecMsg::UpdateIbcDestination { channel_id: "channel-0".to_owned(), remote_address: Some("remote".to_owned()) },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { required_role: Role::Owner });
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::UpdateIbcDestination { channel_id: "channel-0".to_owned(), remote_address: Some("remote".to_owned()) },
+    ).unwrap();
+
+    let resp = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), withdraw).unwrap();
+
+    let timeout = IbcTimeout::with_timestamp(env.block.time.plus_seconds(300));
+    assert_eq!(
+        resp.messages,
+        vec![
+            SubMsg::new(IbcMsg::Transfer {
+                channel_id: "channel-0".to_owned(),
+                to_address: "remote".to_owned(),
+                amount: Coin::new(100, ATOM),
+                timeout: timeout.clone(),
+            }),
+            SubMsg::new(IbcMsg::Transfer {
+                channel_id: "channel-0".to_owned(),
+                to_address: "remote".to_owned(),
+                amount: Coin::new(50, OSMO),
+                timeout,
+            }),
+        ]
+    );
+}
+
+#[test]
+fn withdraw_over_ibc_with_timeout() {
+    let mut deps = mock_dependencies_with_balance(&coins(100, ATOM));
+    let env = mock_env();
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::UpdateIbcDestination { channel_id: "channel-1".to_owned(), remote_address: Some("remote".to_owned()) },
+    ).unwrap();
+
+    let resp = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::Withdraw {
+            ibc: Some(IbcWithdraw {
+                channel_id: "channel-1".to_owned(),
+                remote_address: "remote".to_owned(),
+                timeout: Some(30),
+            }),
+            campaign: None,
+        },
+    ).unwrap();
+
+    assert_eq!(
+        resp.messages,
+        vec![SubMsg::new(IbcMsg::Transfer {
+            channel_id: "channel-1".to_owned(),
+            to_address: "remote".to_owned(),
+            amount: Coin::new(100, ATOM),
+            timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(30)),
+        })]
+    );
+}
+
+#[test]
+fn withdraw_over_ibc_with_beneficiaries() {
+    let mut deps = mock_dependencies_with_balance(&coins(100, ATOM));
+    let env = mock_env();
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::UpdateIbcDestination { channel_id: "channel-0".to_owned(), remote_address: Some("remote".to_owned()) },
+    ).unwrap();
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::UpdateBeneficiaries {
+            beneficiaries: vec![
+                Beneficiary { address: "alice".to_owned(), weight: Decimal::percent(60) },
+                Beneficiary { address: "bob".to_owned(), weight: Decimal::percent(40) },
+            ],
+        },
+    ).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::Withdraw {
+            ibc: Some(IbcWithdraw {
+                channel_id: "channel-0".to_owned(),
+                remote_address: "remote".to_owned(),
+                timeout: None,
+            }),
+            campaign: None,
+        },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::IbcWithdrawWithBeneficiaries {});
+
+    let resp = execute(deps.as_mut(), env, mock_info("owner", &[]), ExecMsg::Withdraw { ibc: None, campaign: None }).unwrap();
+    assert_eq!(
+        resp.messages,
+        vec![
+            SubMsg::new(BankMsg::Send { to_address: "alice".to_owned(), amount: coins(60, ATOM) }),
+            SubMsg::new(BankMsg::Send { to_address: "bob".to_owned(), amount: coins(40, ATOM) }),
+        ]
+    );
+}
+
+fn trust_counterparty(deps: DepsMut) {
+    execute(
+        deps,
+        mock_env(),
+        mock_info("owner", &[]),
+        ExecMsg::UpdateIbcCounterparty {
+            connection_id: "connection-2".to_owned(),
+            counterparty: Some(IbcCounterparty { port_id: "their_port".to_owned(), transfer_channel_id: "channel-1".to_owned() }),
+        },
+    ).unwrap();
+}
+
+#[test]
+fn ibc_channel_handshake() {
+    let mut deps = mock_dependencies();
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let err = ibc_channel_open(deps.as_mut(), mock_env(), mock_ibc_channel_open_try("channel-0", IbcOrder::Unordered, IBC_VERSION)).unwrap_err();
+    assert_eq!(err, ContractError::UntrustedIbcConnection { connection_id: "connection-2".to_owned() });
+
+    trust_counterparty(deps.as_mut());
+
+    let err = ibc_channel_open(deps.as_mut(), mock_env(), mock_ibc_channel_open_try("channel-0", IbcOrder::Ordered, IBC_VERSION)).unwrap_err();
+    assert_eq!(err, ContractError::InvalidIbcOrder {});
+
+    let err = ibc_channel_open(deps.as_mut(), mock_env(), mock_ibc_channel_open_try("channel-0", IbcOrder::Unordered, "ics20-1")).unwrap_err();
+    assert_eq!(err, ContractError::InvalidIbcVersion { expected: IBC_VERSION.to_owned(), version: "ics20-1".to_owned() });
+
+    ibc_channel_open(deps.as_mut(), mock_env(), mock_ibc_channel_open_try("channel-0", IbcOrder::Unordered, IBC_VERSION)).unwrap();
+}
+
+#[test]
+fn ibc_count_received_donations() {
+    let mut deps = mock_dependencies();
+
+    instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let packet = CountingPacket::V1 { id: 1, donations: 1, funds: coins(10, ATOM) };
+    let resp = ibc_packet_receive(deps.as_mut(), mock_env(), mock_ibc_packet_recv("channel-0", &packet).unwrap()).unwrap();
+    assert!(matches!(from_json::<CountingAck>(&resp.acknowledgement).unwrap(), CountingAck::Error(_)));
+
+    trust_counterparty(deps.as_mut());
+    ibc_channel_connect(deps.as_mut(), mock_env(), mock_ibc_channel_connect_ack("channel-0", IbcOrder::Unordered, IBC_VERSION)).unwrap();
+
+    let resp = ibc_packet_receive(deps.as_mut(), mock_env(), mock_ibc_packet_recv("channel-0", &packet).unwrap()).unwrap();
+    assert_eq!(from_json::<CountingAck>(&resp.acknowledgement).unwrap(), CountingAck::Result(to_json_binary(&0u64).unwrap()));
+
+    let resp = ibc_packet_receive(deps.as_mut(), mock_env(), mock_ibc_packet_recv("channel-0", &packet).unwrap()).unwrap();
+    assert!(matches!(from_json::<CountingAck>(&resp.acknowledgement).unwrap(), CountingAck::Error(_)));
+
+    let resp = ibc_packet_receive(deps.as_mut(), mock_env(), mock_ibc_packet_recv("channel-0", &"garbage").unwrap()).unwrap();
+    assert!(matches!(from_json::<CountingAck>(&resp.acknowledgement).unwrap(), CountingAck::Error(_)));
+
+    let settle = ExecMsg::SettleIbcDonation { channel_id: "channel-0".to_owned(), packet_id: 1 };
+    let denom = ibc_denom("channel-1", ATOM);
+    let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), settle.clone()).unwrap_err();
+    assert_eq!(err, ContractError::IbcFundsNotReceived { denom: denom.clone() });
+
+    deps.querier.update_balance(MOCK_CONTRACT_ADDR, coins(10, &denom));
+    let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), settle.clone()).unwrap_err();
+    assert_eq!(err, ContractError::IbcFundsNotReceived { denom: denom.clone() });
+
+    let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &coins(10, ATOM)), settle.clone()).unwrap_err();
+    assert_eq!(err, ContractError::UnexpectedIbcFunds { denom: ATOM.to_owned() });
+
+    execute(deps.as_mut(), mock_env(), mock_info("relayer", &coins(10, &denom)), settle.clone()).unwrap();
+
+    let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), settle).unwrap_err();
+    assert_eq!(err, ContractError::UnknownIbcDonation { channel_id: "channel-0".to_owned(), packet_id: 1 });
+
+    let packet = CountingPacket::V1 { id: 2, donations: 1, funds: coins(10, ATOM) };
+    ibc_packet_receive(deps.as_mut(), mock_env(), mock_ibc_packet_recv("channel-0", &packet).unwrap()).unwrap();
+    let settle = ExecMsg::SettleIbcDonation { channel_id: "channel-0".to_owned(), packet_id: 2 };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &[]), settle.clone()).unwrap_err();
+    assert_eq!(err, ContractError::IbcFundsNotReceived { denom: denom.clone() });
+
+    execute(deps.as_mut(), mock_env(), mock_info("relayer", &coins(4, &denom)), settle.clone()).unwrap();
+    let resp: ValueResp = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Value { campaign: None }).unwrap()).unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+
+    let err = execute(deps.as_mut(), mock_env(), mock_info("relayer", &coins(7, &denom)), settle.clone()).unwrap_err();
+    assert_eq!(err, ContractError::UnexpectedIbcFunds { denom: denom.clone() });
+
+    execute(deps.as_mut(), mock_env(), mock_info("relayer", &coins(6, &denom)), settle).unwrap();
+    let resp: ValueResp = from_json(query(deps.as_ref(), mock_env(), QueryMsg::Value { campaign: None }).unwrap()).unwrap();
+    assert_eq!(resp, ValueResp { value: 2 });
+}
+
+#[test]
+fn ibc_parent_share() {
+    let mut deps = mock_dependencies_with_balance(&coins(100, ATOM));
+    let env = mock_env();
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            parent: Some(Parent {
+                addr: "remote".to_owned(),
+                donating_period: 1,
+                part: Decimal::percent(10),
+                payout_interval: None,
+                keeper_fee: None,
+                ibc: Some(IbcParent { channel_id: "channel-0".to_owned(), transfer_channel_id: "channel-1".to_owned() }),
+            }),
+            ibc_timeout: Some(300),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let resp = execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(100, ATOM)), ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None }).unwrap();
+    let packet = CountingPacket::V1 { id: 1, donations: 1, funds: coins(10, ATOM) };
+    let timeout = IbcTimeout::with_timestamp(env.block.time.plus_seconds(300));
+    assert_eq!(
+        resp.messages,
+        vec![SubMsg::new(IbcMsg::SendPacket {
+            channel_id: "channel-0".to_owned(),
+            data: to_json_binary(&packet).unwrap(),
+            timeout: timeout.clone(),
+        })]
+    );
+
+    let ack = IbcAcknowledgement::new(to_json_binary(&CountingAck::Result(to_json_binary(&1u64).unwrap())).unwrap());
+    let resp = ibc_packet_ack(deps.as_mut(), env.clone(), mock_ibc_packet_ack("channel-0", &packet, ack).unwrap()).unwrap();
+    assert_eq!(
+        resp.messages,
+        vec![SubMsg::new(IbcMsg::Transfer {
+            channel_id: "channel-1".to_owned(),
+            to_address: "remote".to_owned(),
+            amount: Coin::new(10, ATOM),
+            timeout,
+        })]
+    );
+
+    execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(100, ATOM)), ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None }).unwrap();
+    let packet = CountingPacket::V1 { id: 2, donations: 1, funds: coins(10, ATOM) };
+    let ack = IbcAcknowledgement::new(Binary::from(b"garbage".to_vec()));
+    let resp = ibc_packet_ack(deps.as_mut(), env.clone(), mock_ibc_packet_ack("channel-0", &packet, ack).unwrap()).unwrap();
+    assert_eq!(resp.messages, vec![]);
+
+    execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(100, ATOM)), ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None }).unwrap();
+    let packet = CountingPacket::V1 { id: 3, donations: 1, funds: coins(10, ATOM) };
+    let resp = ibc_packet_timeout(deps.as_mut(), env.clone(), mock_ibc_packet_timeout("channel-0", &packet).unwrap()).unwrap();
+    assert_eq!(resp.messages, vec![]);
+
+    let resp = execute(deps.as_mut(), env, mock_info("owner", &[]), ExecMsg::Withdraw { ibc: None, campaign: None }).unwrap();
+    assert_eq!(
+        resp.messages,
+        vec![SubMsg::new(BankMsg::Send { to_address: "owner".to_owned(), amount: coins(100, ATOM) })]
+    );
+}
+
+#[test]
+fn redeem_ed25519_voucher() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            voucher_key: Some(VoucherKey::Ed25519(Binary::from_base64(ED25519_KEY).unwrap())),
+            milestones: Some(vec![Milestone { threshold: 1, action: MilestoneAction::Event {} }]),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let signature = Binary::from_base64(ED25519_SIGNATURE).unwrap();
+    let mut other_chain = env.clone();
+    other_chain.block.chain_id = "other-chain".to_owned();
+    let err = execute(
+        deps.as_mut(),
+        other_chain,
+        mock_info("relayer", &[]),
+        ExecMsg::RedeemVoucher { voucher: voucher(1), signature: signature.clone() },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::InvalidVoucherSignature { nonce: 1 });
+
+    let resp = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("relayer", &[]),
+        ExecMsg::RedeemVoucher { voucher: voucher(1), signature: signature.clone() },
+    ).unwrap();
+    assert_eq!(resp.events, vec![Event::new("milestone").add_attribute("threshold", "1").add_attribute("counter", "1")]);
+
+    let resp: ValueResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Value { campaign: None }).unwrap()).unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+
+    let resp: VoucherResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Voucher { nonce: 1 }).unwrap()).unwrap();
+    assert_eq!(resp, VoucherResp { nonce: 1, redeemed_by: Some(Addr::unchecked("donor")) });
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("relayer", &[]),
+        ExecMsg::RedeemVoucher { voucher: voucher(1), signature: signature.clone() },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::VoucherRedeemed { nonce: 1 });
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("relayer", &[]),
+        ExecMsg::RedeemVoucher { voucher: voucher(3), signature: signature.clone() },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::InvalidVoucherSignature { nonce: 3 });
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("relayer", &[]),
+        ExecMsg::RedeemVoucher {
+            voucher: Voucher { amount: Coin::new(5, ATOM), ..voucher(4) },
+            signature: Binary::from_base64(ED25519_LOW_SIGNATURE).unwrap(),
+        },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::VoucherBelowMinimum { nonce: 4, minimal_donation: Coin::new(10, ATOM) });
+
+    env.block.height = 12_400;
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info("relayer", &[]),
+        ExecMsg::RedeemVoucher { voucher: voucher(3), signature },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::VoucherExpired { nonce: 3 });
+}
+
+#[test]
+fn redeem_secp256k1_voucher() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let signature = Binary::from_base64(SECP256K1_SIGNATURE).unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("relayer", &[]),
+        ExecMsg::RedeemVoucher { voucher: voucher(2), signature: signature.clone() },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::VoucherKeyNotSet {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::UpdateVoucherKey { key: Some(VoucherKey::Secp256k1(Binary::from_base64(SECP256K1_KEY).unwrap())) },
+    ).unwrap();
+
+    execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecMsg::UpdateAccessMode { mode: AccessMode::Blocklist }).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::UpdateAccessList { list: AccessList::Blocklist, add: vec!["donor".to_owned()], remove: vec![] },
+    ).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("relayer", &[]),
+        ExecMsg::RedeemVoucher { voucher: voucher(2), signature: signature.clone() },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::DonorNotAllowed { donor: Addr::unchecked("donor") });
+
+    execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecMsg::UpdateAccessMode { mode: AccessMode::Open }).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("relayer", &[]),
+        ExecMsg::RedeemVoucher { voucher: voucher(2), signature },
+    ).unwrap();
+
+    let resp: ValueResp = from_json(query(deps.as_ref(), env, QueryMsg::Value { campaign: None }).unwrap()).unwrap();
+    assert_eq!(resp, ValueResp { value: 1 });
+}
+
+#[test]
+fn counter_history() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+    let height = env.block.height;
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    env.block.height += 1;
+    execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(10, ATOM)), ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None }).unwrap();
+    execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(10, ATOM)), ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None }).unwrap();
+
+    env.block.height += 2;
+    execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(10, ATOM)), ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None }).unwrap();
+
+    for (at, value) in [(height - 1, 0), (height, 0), (height + 1, 2), (height + 2, 2), (height + 3, 3), (height + 10, 3)] {
+        let resp: ValueResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::ValueAt { height: at }).unwrap()).unwrap();
+        assert_eq!(resp, ValueResp { value }, "height {}", at);
+    }
+
+    let resp: ChangelogResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Changelog { start_after: None, limit: None }).unwrap()).unwrap();
+    assert_eq!(
+        resp.changes,
+        vec![
+            CounterChange { height, value: 0 },
+            CounterChange { height: height + 1, value: 2 },
+            CounterChange { height: height + 3, value: 3 },
+        ]
+    );
+
+    let resp: ChangelogResp = from_json(query(deps.as_ref(), env, QueryMsg::Changelog { start_after: Some(height), limit: Some(1) }).unwrap()).unwrap();
+    assert_eq!(resp.changes, vec![CounterChange { height: height + 1, value: 2 }]);
+}
+
+#[test]
+fn migration_seeds_counter_history() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    #[derive(Deserialize, Serialize)]
+    struct OldState {
+        counter: u64,
+        minimal_donation: Coin,
+        donating_parent: Option<u64>,
+    }
+
+    set_contract_version(deps.as_mut().storage, env!("CARGO_PKG_NAME"), "0.3.0").unwrap();
+    Item::new("owner").save(deps.as_mut().storage, &Addr::unchecked("owner")).unwrap();
+    Item::new("state").save(
+        deps.as_mut().storage,
+        &OldState {
+            counter: 7,
+            minimal_donation: Coin::new(10, ATOM),
+            donating_parent: Some(3),
+        },
+    ).unwrap();
+
+    migrate(deps.as_mut(), env.clone(), MigrateMsg { parent: None }).unwrap();
+
+    let resp: ValueResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::ValueAt { height: env.block.height }).unwrap()).unwrap();
+    assert_eq!(resp, ValueResp { value: 7 });
+    assert_eq!(
+        STATE.load(deps.as_ref().storage).unwrap(),
+        State {
+            minimal_donation: Coin::new(10, ATOM),
+            donating_parent: Some(3),
+        },
+    );
+}
+
+#[test]
+fn donation_log() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let memo = Some("for the kids".to_owned());
+    execute(deps.as_mut(), env.clone(), mock_info("alice", &coins(10, ATOM)), ExecMsg::Donate { campaign: None, memo: memo.clone(), beneficiary: None, referrer: None }).unwrap();
+    execute(deps.as_mut(), env.clone(), mock_info("bob", &coins(20, ATOM)), ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None }).unwrap();
+    execute(deps.as_mut(), env.clone(), mock_info("bob", &coins(5, ATOM)), ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None }).unwrap();
+    execute(deps.as_mut(), env.clone(), mock_info("alice", &coins(30, ATOM)), ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None }).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &coins(10, ATOM)),
+        ExecMsg::Donate { campaign: None, memo: Some("x".repeat(257)), beneficiary: None, referrer: None },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::MemoTooLong { max: 256, len: 257 });
+
+    let query_donations = |start_after, limit, donor: Option<&str>| -> Vec<DonationResp> {
+        let msg = QueryMsg::Donations { start_after, limit, donor: donor.map(str::to_owned) };
+        let resp: DonationsResp = from_json(query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        resp.donations
+    };
+
+    let donations = query_donations(None, None, None);
+    assert_eq!(donations.iter().map(|donation| donation.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert_eq!(
+        donations[0],
+        DonationResp {
+            id: 1,
+            donor: Addr::unchecked("alice"),
+            funds: coins(10, ATOM),
+            memo,
+            campaign: None,
+            height: env.block.height,
+            time: env.block.time,
+        }
+    );
+
+    let donations = query_donations(Some(1), Some(1), None);
+    assert_eq!(donations.iter().map(|donation| donation.id).collect::<Vec<_>>(), vec![2]);
+
+    let donations = query_donations(None, None, Some("alice"));
+    assert_eq!(donations.iter().map(|donation| donation.id).collect::<Vec<_>>(), vec![1, 3]);
+
+    let donations = query_donations(Some(1), None, Some("alice"));
+    assert_eq!(donations.iter().map(|donation| donation.id).collect::<Vec<_>>(), vec![3]);
+}
+
+#[test]
+fn leaderboard() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            refund_window: Some(Duration::Height(10)),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    for (donor, funds) in [("alice", coins(30, ATOM)), ("bob", coins(50, ATOM)), ("carol", coins(10, ATOM)), ("alice", coins(25, ATOM)), ("dave", coins(100, OSMO))] {
+        execute(deps.as_mut(), env.clone(), mock_info(donor, &funds), ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None }).unwrap();
+    }
+
+    let query_leaderboard = |deps: Deps, denom: &str, limit| -> Vec<LeaderboardEntry> {
+        let msg = QueryMsg::Leaderboard { denom: denom.to_owned(), limit };
+        let resp: LeaderboardResp = from_json(query(deps, mock_env(), msg).unwrap()).unwrap();
+        resp.donors
+    };
+    let entry = |donor: &str, amount: u128| LeaderboardEntry { donor: Addr::unchecked(donor), amount: Uint128::new(amount) };
+
+    assert_eq!(query_leaderboard(deps.as_ref(), ATOM, None), vec![entry("alice", 55), entry("bob", 50), entry("carol", 10)]);
+    assert_eq!(query_leaderboard(deps.as_ref(), ATOM, Some(1)), vec![entry("alice", 55)]);
+    assert_eq!(query_leaderboard(deps.as_ref(), OSMO, None), vec![entry("dave", 100)]);
+
+    execute(deps.as_mut(), env.clone(), mock_info("bob", &[]), ExecMsg::Reclaim { donation_id: 2 }).unwrap();
+    execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), ExecMsg::Reclaim { donation_id: 4 }).unwrap();
+    assert_eq!(query_leaderboard(deps.as_ref(), ATOM, None), vec![entry("alice", 30), entry("carol", 10)]);
+}
+
+#[test]
+fn donate_on_behalf_with_referral() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            refund_window: Some(Duration::Height(10)),
+            referral_part: Some(Decimal::percent(10)),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("exchange", &coins(100, ATOM)),
+        ExecMsg::Donate { campaign: None, memo: None, beneficiary: Some("alice".to_owned()), referrer: Some("alice".to_owned()) },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::SelfReferral {});
+
+    let resp = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("exchange", &coins(100, ATOM)),
+        ExecMsg::Donate { campaign: None, memo: None, beneficiary: Some("alice".to_owned()), referrer: Some("bob".to_owned()) },
+    ).unwrap();
+    assert_eq!(
+        resp.messages,
+        vec![SubMsg::new(BankMsg::Send { to_address: "bob".to_owned(), amount: coins(10, ATOM) })]
+    );
+
+    let resp: ContributionsResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Contributions { donor: "alice".to_owned() }).unwrap()).unwrap();
+    assert_eq!(resp.contributions, coins(90, ATOM));
+    let resp: ContributionsResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Contributions { donor: "exchange".to_owned() }).unwrap()).unwrap();
+    assert_eq!(resp.contributions, vec![]);
+
+    let resp: ReferralsResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Referrals { referrer: "bob".to_owned() }).unwrap()).unwrap();
+    assert_eq!(resp.rewards, coins(10, ATOM));
+
+    let err = execute(deps.as_mut(), env.clone(), mock_info("alice", &[]), ExecMsg::Reclaim { donation_id: 1 }).unwrap_err();
+    assert_eq!(err, ContractError::NotDonor { donation_id: 1 });
+
+    let resp = execute(deps.as_mut(), env.clone(), mock_info("exchange", &[]), ExecMsg::Reclaim { donation_id: 1 }).unwrap();
+    assert_eq!(
+        resp.messages,
+        vec![SubMsg::new(BankMsg::Send { to_address: "exchange".to_owned(), amount: coins(90, ATOM) })]
+    );
+
+    let resp: ContributionsResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Contributions { donor: "alice".to_owned() }).unwrap()).unwrap();
+    assert_eq!(resp.contributions, coins(0, ATOM));
+
+    let resp: ReferralsResp = from_json(query(deps.as_ref(), env, QueryMsg::Referrals { referrer: "bob".to_owned() }).unwrap()).unwrap();
+    assert_eq!(resp.rewards, vec![]);
+}
+
+#[test]
+fn access_lists() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            access_mode: Some(AccessMode::Allowlist),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let donate = |deps: DepsMut, sender: &str, beneficiary: Option<&str>| {
+        let msg = ExecMsg::Donate { campaign: None, memo: None, beneficiary: beneficiary.map(str::to_owned), referrer: None };
+        execute(deps, mock_env(), mock_info(sender, &coins(10, ATOM)), msg)
+    };
+
+    let err = donate(deps.as_mut(), "alice", None).unwrap_err();
+    assert_eq!(err, ContractError::DonorNotAllowed { donor: Addr::unchecked("alice") });
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("alice", &[]),
+        ExecMsg::UpdateAccessList { list: AccessList::Allowlist, add: vec!["alice".to_owned()], remove: vec![] },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { required_role: Role::Owner });
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::UpdateAccessList { list: AccessList::Allowlist, add: vec!["alice".to_owned(), "bob".to_owned(), "carol".to_owned()], remove: vec!["carol".to_owned()] },
+    ).unwrap();
+
+    donate(deps.as_mut(), "alice", None).unwrap();
+    let err = donate(deps.as_mut(), "alice", Some("dave")).unwrap_err();
+    assert_eq!(err, ContractError::DonorNotAllowed { donor: Addr::unchecked("dave") });
+
+    let resp: AccessListResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::AccessList { list: AccessList::Allowlist, start_after: None, limit: None }).unwrap()).unwrap();
+    assert_eq!(resp.addresses, vec![Addr::unchecked("alice"), Addr::unchecked("bob")]);
+    let resp: AccessListResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::AccessList { list: AccessList::Allowlist, start_after: Some("alice".to_owned()), limit: Some(1) }).unwrap()).unwrap();
+    assert_eq!(resp.addresses, vec![Addr::unchecked("bob")]);
+
+    execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecMsg::UpdateAccessMode { mode: AccessMode::Blocklist }).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::UpdateAccessList { list: AccessList::Blocklist, add: vec!["bob".to_owned()], remove: vec![] },
+    ).unwrap();
+
+    donate(deps.as_mut(), "dave", None).unwrap();
+    let err = donate(deps.as_mut(), "bob", None).unwrap_err();
+    assert_eq!(err, ContractError::DonorNotAllowed { donor: Addr::unchecked("bob") });
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("dave", &coins(10, ATOM)),
+        ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: Some("bob".to_owned()) },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::DonorNotAllowed { donor: Addr::unchecked("bob") });
+
+    let resp: ValueResp = from_json(query(deps.as_ref(), env, QueryMsg::Value { campaign: None }).unwrap()).unwrap();
+    assert_eq!(resp, ValueResp { value: 2 });
+}
+
+#[test]
+fn rate_limited_donations() {
+    let mut deps = mock_dependencies();
+    let mut env = mock_env();
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            rate_limit: Some(RateLimit {
+                cooldown: Some(Duration::Height(1)),
+                window: Some(DonationWindow { duration: Duration::Height(10), max_counted: 2 }),
+                on_exceeded: LimitAction::Reject,
+            }),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let donate = |deps: DepsMut, env: &Env| {
+        let msg = ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None };
+        execute(deps, env.clone(), mock_info("sender", &coins(10, ATOM)), msg)
+    };
+    let value = |deps: Deps| -> u64 {
+        let resp: ValueResp = from_json(query(deps, mock_env(), QueryMsg::Value { campaign: None }).unwrap()).unwrap();
+        resp.value
+    };
+
+    donate(deps.as_mut(), &env).unwrap();
+    let err = donate(deps.as_mut(), &env).unwrap_err();
+    assert_eq!(err, ContractError::RateLimited { donor: Addr::unchecked("sender"), available_at: Expiration::AtHeight(env.block.height + 1) });
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("sender", &coins(10, ATOM)),
+        ExecMsg::Donate { campaign: None, memo: None, beneficiary: Some("alice".to_owned()), referrer: None },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::RateLimited { donor: Addr::unchecked("sender"), available_at: Expiration::AtHeight(env.block.height + 1) });
+
+    env.block.height += 1;
+    donate(deps.as_mut(), &env).unwrap();
+
+    env.block.height += 1;
+    let err = donate(deps.as_mut(), &env).unwrap_err();
+    assert_eq!(err, ContractError::RateLimited { donor: Addr::unchecked("sender"), available_at: Expiration::AtHeight(env.block.height + 8) });
+    assert_eq!(value(deps.as_ref()), 2);
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::UpdateRateLimit {
+            limit: Some(RateLimit {
+                cooldown: Some(Duration::Height(1)),
+                window: Some(DonationWindow { duration: Duration::Height(10), max_counted: 2 }),
+                on_exceeded: LimitAction::Uncount,
+            }),
+        },
+    ).unwrap();
+
+    let resp = donate(deps.as_mut(), &env).unwrap();
+    assert!(resp.attributes.iter().any(|attr| attr.key == "rate_limited"));
+    assert_eq!(value(deps.as_ref()), 2);
+
+    env.block.height += 8;
+    donate(deps.as_mut(), &env).unwrap();
+    assert_eq!(value(deps.as_ref()), 3);
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::CreateCampaign { name: "school".to_owned(), minimal_donation: Coin::new(10, ATOM), owner: None, parent: None },
+    ).unwrap();
+    let msg = ExecMsg::Donate { campaign: Some("school".to_owned()), memo: None, beneficiary: None, referrer: None };
+    let resp = execute(deps.as_mut(), env.clone(), mock_info("sender", &coins(10, ATOM)), msg).unwrap();
+    assert!(resp.attributes.iter().any(|attr| attr.key == "rate_limited"));
+
+    let resp: ValueResp = from_json(query(deps.as_ref(), env, QueryMsg::Value { campaign: Some("school".to_owned()) }).unwrap()).unwrap();
+    assert_eq!(resp.value, 0);
+}
+
+#[test]
+fn milestones() {
+    let mut deps = mock_dependencies_with_balance(&coins(100, ATOM));
+    let env = mock_env();
+
+    let charity = BankMsg::Send { to_address: "charity".to_owned(), amount: coins(5, ATOM) };
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            parent: Some(Parent {
+                addr: "parent".to_owned(),
+                donating_period: 100,
+                part: Decimal::percent(10),
+                payout_interval: None,
+                keeper_fee: None,
+                ibc: None,
+            }),
+            milestones: Some(vec![
+                Milestone { threshold: 1, action: MilestoneAction::Event {} },
+                Milestone { threshold: 2, action: MilestoneAction::NotifyParent {} },
+            ]),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let donate = |deps: DepsMut| {
+        let msg = ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None };
+        execute(deps, mock_env(), mock_info("sender", &coins(10, ATOM)), msg).unwrap()
+    };
+
+    let resp = donate(deps.as_mut());
+    assert_eq!(resp.messages, vec![]);
+    assert_eq!(resp.events, vec![Event::new("milestone").add_attribute("threshold", "1").add_attribute("counter", "1")]);
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::AddMilestones { milestones: vec![Milestone { threshold: 1, action: MilestoneAction::Event {} }] },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::MilestonePassed { threshold: 1, counter: 1 });
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::AddMilestones { milestones: vec![Milestone { threshold: 2, action: MilestoneAction::Event {} }] },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::MilestoneExists { threshold: 2 });
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::AddMilestones { milestones: vec![Milestone { threshold: 3, action: MilestoneAction::Message { msg: charity.clone().into() } }] },
+    ).unwrap();
+    execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecMsg::RemoveMilestone { threshold: 3 }).unwrap();
+
+    let burn = BankMsg::Burn { amount: coins(5, ATOM) };
+    let propose = |deps: DepsMut, milestones: Vec<Milestone>| {
+        let msg = ExecMsg::Propose { action: ProposalAction::AddMilestones { milestones } };
+        execute(deps, mock_env(), mock_info("owner", &[]), msg).unwrap();
+    };
+    propose(deps.as_mut(), vec![Milestone { threshold: 3, action: MilestoneAction::Message { msg: burn.into() } }]);
+    let err = execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecMsg::Execute { id: 1 }).unwrap_err();
+    assert_eq!(err, ContractError::UnsupportedMilestoneMessage { threshold: 3 });
+
+    let drain = BankMsg::Send { to_address: "charity".to_owned(), amount: coins(1000, ATOM) };
+    propose(deps.as_mut(), vec![
+        Milestone { threshold: 3, action: MilestoneAction::Message { msg: charity.clone().into() } },
+        Milestone { threshold: 5, action: MilestoneAction::Message { msg: drain.into() } },
+    ]);
+    execute(deps.as_mut(), env.clone(), mock_info("owner", &[]), ExecMsg::Execute { id: 2 }).unwrap();
+
+    let resp = donate(deps.as_mut());
+    assert_eq!(
+        resp.messages,
+        vec![SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: "parent".to_owned(),
+                msg: to_json_binary(&ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None }).unwrap(),
+                funds: coins(10, ATOM),
+            },
+            PARENT_DONATION_REPLY_ID,
+        )]
+    );
+
+    let resp: MilestonesResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Milestones {}).unwrap()).unwrap();
+    assert_eq!(resp.reached.iter().map(|milestone| milestone.threshold).collect::<Vec<_>>(), vec![1, 2]);
+    assert_eq!(resp.reached[0].reached_at, Some(env.block.height));
+    assert_eq!(resp.pending.iter().map(|milestone| milestone.threshold).collect::<Vec<_>>(), vec![3, 5]);
+
+    let resp = donate(deps.as_mut());
+    assert_eq!(resp.messages, vec![SubMsg::reply_on_error(charity, MILESTONE_REPLY_FLAG | 3)]);
+
+    let failed = Reply { id: MILESTONE_REPLY_FLAG | 3, result: SubMsgResult::Err("insufficient funds".to_owned()) };
+    reply(deps.as_mut(), env.clone(), failed).unwrap();
+    let resp: MilestonesResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Milestones {}).unwrap()).unwrap();
+    assert_eq!(resp.reached[2].failed, Some("insufficient funds".to_owned()));
+
+    let resp = donate(deps.as_mut());
+    assert_eq!(resp.messages, vec![]);
+    assert!(resp.events.is_empty());
+
+    let resp = donate(deps.as_mut());
+    assert_eq!(resp.messages, vec![]);
+    assert!(resp.events[0].attributes.iter().any(|attr| attr.key == "skipped"));
+}
+
+#[test]
+fn message_milestones_under_governance() {
+    let mut deps = mock_dependencies_with_balance(&coins(100, ATOM));
+
+    let charity = BankMsg::Send { to_address: "charity".to_owned(), amount: coins(5, ATOM) };
+    let err = instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            governance: Some(Governance {
+                quorum: Decimal::percent(50),
+                threshold: Decimal::percent(50),
+                voting_period: Duration::Time(100),
+            }),
+            milestones: Some(vec![Milestone { threshold: 1, action: MilestoneAction::Message { msg: charity.into() } }]),
+            ..Default::default()
+        },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::SpendingProposalRequired {});
+}
+
+#[test]
+fn campaign_donations_bookkeeping() {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            refund_window: Some(Duration::Height(10)),
+            counting_mode: Some(CountingMode::Proportional { cap: None }),
+            milestones: Some(vec![Milestone { threshold: 1, action: MilestoneAction::Event {} }]),
+            ..Default::default()
+        },
+    ).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("owner", &[]),
+        ExecMsg::CreateCampaign { name: "school".to_owned(), minimal_donation: Coin::new(10, ATOM), owner: None, parent: None },
+    ).unwrap();
+
+    let msg = ExecMsg::Donate { campaign: Some("school".to_owned()), memo: None, beneficiary: None, referrer: None };
+    let resp = execute(deps.as_mut(), env.clone(), mock_info("alice", &coins(100, ATOM)), msg).unwrap();
+    assert!(resp.events.is_empty());
+
+    let value = |deps: Deps, campaign: Option<&str>| -> u64 {
+        let resp: ValueResp = from_json(query(deps, mock_env(), QueryMsg::Value { campaign: campaign.map(str::to_owned) }).unwrap()).unwrap();
+        resp.value
+    };
+    assert_eq!(value(deps.as_ref(), Some("school")), 1);
+    assert_eq!(value(deps.as_ref(), None), 0);
+
+    let resp: ContributionsResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Contributions { donor: "alice".to_owned() }).unwrap()).unwrap();
+    assert_eq!(resp.contributions, vec![]);
+
+    let resp: LeaderboardResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Leaderboard { denom: ATOM.to_owned(), limit: None }).unwrap()).unwrap();
+    assert_eq!(resp.donors, vec![]);
+
+    let resp: DonationsResp = from_json(query(deps.as_ref(), env.clone(), QueryMsg::Donations { start_after: None, limit: None, donor: None }).unwrap()).unwrap();
+    assert_eq!(resp.donations.iter().map(|donation| donation.campaign.as_deref()).collect::<Vec<_>>(), vec![Some("school")]);
+
+    execute(deps.as_mut(), env, mock_info("alice", &[]), ExecMsg::Reclaim { donation_id: resp.donations[0].id }).unwrap_err();
+}
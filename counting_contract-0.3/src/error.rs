@@ -1,14 +1,36 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Addr, Coin, Decimal, StdError, Timestamp};
+use cw_utils::Expiration;
 use thiserror::Error;
 
+use crate::msg::Role;
+
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
-    #[error("Unauthorized - only {owner} can call it")]
+    #[error("Unauthorized - {required_role:?} role required")]
     Unauthorized {
-        owner: String,
+        required_role: Role,
+    },
+
+    #[error("Contract is paused")]
+    Paused {},
+
+    #[error("Donations from {donor} are rate limited until {available_at}")]
+    RateLimited {
+        donor: Addr,
+        available_at: Expiration,
+    },
+
+    #[error("Donations from {donor} are not accepted")]
+    DonorNotAllowed {
+        donor: Addr,
+    },
+
+    #[error("Only the donor can reclaim donation {donation_id}")]
+    NotDonor {
+        donation_id: u64,
     },
 
     #[error("Migrating invalid contract: {0}")]
@@ -16,4 +38,264 @@ pub enum ContractError {
 
     #[error("Migrating from unsuported version: {0}")]
     InvalidVersion(String),
+
+    #[error("Parent payout is not due yet - next payout {next_payout}")]
+    PayoutNotDue {
+        next_payout: Expiration,
+    },
+
+    #[error("Keeper fee must not exceed 100%, got {0}")]
+    InvalidKeeperFee(Decimal),
+
+    #[error("Refund window for donation {donation_id} has expired")]
+    RefundWindowExpired {
+        donation_id: u64,
+    },
+
+    #[error("Withdraw limit exceeded - next withdrawal possible at {available_at}")]
+    WithdrawLimitExceeded {
+        available_at: Timestamp,
+    },
+
+    #[error("Insufficient withdrawable funds - requested {requested}, available {available}")]
+    InsufficientFunds {
+        requested: Coin,
+        available: Coin,
+    },
+
+    #[error("Invalid threshold {threshold} for {members} owners")]
+    InvalidThreshold {
+        threshold: u64,
+        members: u64,
+    },
+
+    #[error("This action requires a proposal approved by {threshold} owners")]
+    ProposalRequired {
+        threshold: u64,
+    },
+
+    #[error("Proposal {proposal_id} already approved by sender")]
+    AlreadyApproved {
+        proposal_id: u64,
+    },
+
+    #[error("Proposal {proposal_id} has not reached the approval threshold")]
+    ThresholdNotReached {
+        proposal_id: u64,
+    },
+
+    #[error("Proposal {proposal_id} has expired")]
+    ProposalExpired {
+        proposal_id: u64,
+    },
+
+    #[error("Proposal {proposal_id} was already executed")]
+    ProposalExecuted {
+        proposal_id: u64,
+    },
+
+    #[error("Memo must not exceed {max} characters, got {len}")]
+    MemoTooLong {
+        max: usize,
+        len: usize,
+    },
+
+    #[error("Counting cap must not exceed {max} donations, got {cap}")]
+    InvalidCountingCap {
+        cap: u64,
+        max: u64,
+    },
+
+    #[error("Referral part must not exceed 100%, got {part}")]
+    InvalidReferralPart {
+        part: Decimal,
+    },
+
+    #[error("Donors can't refer themselves")]
+    SelfReferral {},
+
+    #[error("Sink shares must not exceed 100%, got {total}")]
+    InvalidSinkShares {
+        total: Decimal,
+    },
+
+    #[error("Beneficiary weights must sum to 100%, got {total}")]
+    InvalidWeights {
+        total: Decimal,
+    },
+
+    #[error("Quorum and threshold must not exceed 100%")]
+    InvalidGovernance {},
+
+    #[error("Donor governance is not enabled")]
+    GovernanceDisabled {},
+
+    #[error("Withdrawals require a spending proposal approved by donors")]
+    SpendingProposalRequired {},
+
+    #[error("Sender has no recorded contribution to vote with")]
+    NoVotingPower {},
+
+    #[error("Sender already voted on proposal {proposal_id}")]
+    AlreadyVoted {
+        proposal_id: u64,
+    },
+
+    #[error("Voting on proposal {proposal_id} is closed")]
+    VotingClosed {
+        proposal_id: u64,
+    },
+
+    #[error("Voting on proposal {proposal_id} is still open")]
+    VotingOpen {
+        proposal_id: u64,
+    },
+
+    #[error("Proposal {proposal_id} did not pass")]
+    ProposalRejected {
+        proposal_id: u64,
+    },
+
+    #[error("{remote_address} is not the withdraw destination configured for channel {channel_id}")]
+    UnauthorizedIbcDestination {
+        channel_id: String,
+        remote_address: String,
+    },
+
+    #[error("Funds are split between beneficiaries and cannot be withdrawn over IBC")]
+    IbcWithdrawWithBeneficiaries {},
+
+    #[error("Only unordered IBC channels are supported")]
+    InvalidIbcOrder {},
+
+    #[error("Invalid IBC channel version - expected {expected}, got {version}")]
+    InvalidIbcVersion {
+        expected: String,
+        version: String,
+    },
+
+    #[error("Connection {connection_id} is not a trusted counterparty")]
+    UntrustedIbcConnection {
+        connection_id: String,
+    },
+
+    #[error("Channel {channel_id} is not a trusted channel")]
+    UntrustedIbcChannel {
+        channel_id: String,
+    },
+
+    #[error("Donations from packet {packet_id} on channel {channel_id} were already received")]
+    IbcDonationExists {
+        channel_id: String,
+        packet_id: u64,
+    },
+
+    #[error("No pending donations from packet {packet_id} on channel {channel_id}")]
+    UnknownIbcDonation {
+        channel_id: String,
+        packet_id: u64,
+    },
+
+    #[error("Transferred {denom} funds were not received yet")]
+    IbcFundsNotReceived {
+        denom: String,
+    },
+
+    #[error("Received {denom} funds exceed the transferred amount")]
+    UnexpectedIbcFunds {
+        denom: String,
+    },
+
+    #[error("Counter overflow")]
+    CounterOverflow {},
+
+    #[error("No voucher signing key configured")]
+    VoucherKeyNotSet {},
+
+    #[error("Invalid signature for voucher {nonce}")]
+    InvalidVoucherSignature {
+        nonce: u64,
+    },
+
+    #[error("Voucher {nonce} expired")]
+    VoucherExpired {
+        nonce: u64,
+    },
+
+    #[error("Voucher {nonce} is below the minimal donation of {minimal_donation}")]
+    VoucherBelowMinimum {
+        nonce: u64,
+        minimal_donation: Coin,
+    },
+
+    #[error("Voucher {nonce} already redeemed")]
+    VoucherRedeemed {
+        nonce: u64,
+    },
+
+    #[error("Matching pool requires a single-denom deposit and a future deadline")]
+    InvalidMatchingPool {},
+
+    #[error("A matching pool is already funded")]
+    MatchingPoolExists {},
+
+    #[error("No matching pool funded")]
+    NoMatchingPool {},
+
+    #[error("Only the sponsor can reclaim the matching pool")]
+    NotSponsor {},
+
+    #[error("Matching pool can't be reclaimed before {deadline}")]
+    MatchingPoolActive {
+        deadline: Expiration,
+    },
+
+    #[error("Campaign {name} already exists")]
+    CampaignExists {
+        name: String,
+    },
+
+    #[error("Campaign {name} not found")]
+    UnknownCampaign {
+        name: String,
+    },
+
+    #[error("Only the owner of campaign {name} can withdraw its funds")]
+    NotCampaignOwner {
+        name: String,
+    },
+
+    #[error("Milestone {threshold} is already registered")]
+    MilestoneExists {
+        threshold: u64,
+    },
+
+    #[error("Milestone {threshold} was already passed - counter is {counter}")]
+    MilestonePassed {
+        threshold: u64,
+        counter: u64,
+    },
+
+    #[error("Milestone {threshold} not found")]
+    UnknownMilestone {
+        threshold: u64,
+    },
+
+    #[error("No parent to notify")]
+    NoParent {},
+
+    #[error("Milestone {threshold} is too high to carry a message")]
+    MilestoneOutOfRange {
+        threshold: u64,
+    },
+
+    #[error("Milestone {threshold} message must be a bank send or a wasm execute")]
+    UnsupportedMilestoneMessage {
+        threshold: u64,
+    },
+
+    #[error("Unknown reply id {id}")]
+    UnknownReply {
+        id: u64,
+    },
 }
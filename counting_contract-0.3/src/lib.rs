@@ -1,7 +1,8 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 
-use cosmwasm_std::{DepsMut, Deps, Env, MessageInfo, StdResult, Response, Binary, to_binary};
+use cosmwasm_std::{DepsMut, Deps, Env, MessageInfo, StdResult, Response, Binary, Reply, to_json_binary};
+use cosmwasm_std::{IbcBasicResponse, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse, IbcPacketAckMsg, IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse};
 use error::ContractError;
 use msg::{InstantiateMsg, MigrateMsg};
 
@@ -16,23 +17,46 @@ pub mod multitest;
 #[cfg_attr(not(feature = "library"),entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
-) -> StdResult<Response> {
-    contract::instantiate(deps, info, msg)
+) -> Result<Response, ContractError> {
+    contract::instantiate(deps, env, info, msg)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(
     deps: Deps,
-    _env: Env,
+    env: Env,
     msg: msg::QueryMsg,
 ) -> StdResult<Binary> {
     use msg::QueryMsg::*;
 
     match msg {
-        Value {} => to_binary(&contract::query::value(deps)?),
+        Value { campaign } => to_json_binary(&contract::query::value(deps, campaign)?),
+        NextPayout {} => to_json_binary(&contract::query::next_payout(deps, env)?),
+        Vesting {} => to_json_binary(&contract::query::vesting(deps, env)?),
+        WithdrawAllowance {} => to_json_binary(&contract::query::withdraw_allowance(deps, env)?),
+        Owners {} => to_json_binary(&contract::query::owners(deps)?),
+        Proposal { id } => to_json_binary(&contract::query::proposal(deps, id)?),
+        Roles { address } => to_json_binary(&contract::query::roles(deps, address)?),
+        SpendingProposal { id } => to_json_binary(&contract::query::spending_proposal(deps, id)?),
+        Contributions { donor } => to_json_binary(&contract::query::contributions(deps, donor)?),
+        Beneficiaries {} => to_json_binary(&contract::query::beneficiaries(deps)?),
+        Staking {} => to_json_binary(&contract::query::staking(deps, env)?),
+        Sinks {} => to_json_binary(&contract::query::sinks(deps)?),
+        Voucher { nonce } => to_json_binary(&contract::query::voucher(deps, nonce)?),
+        MatchingPool {} => to_json_binary(&contract::query::matching_pool(deps)?),
+        RoundContributions { donor } => to_json_binary(&contract::query::round_contributions(deps, donor)?),
+        Campaign { name } => to_json_binary(&contract::query::campaign(deps, name)?),
+        Config {} => to_json_binary(&contract::query::config(deps)?),
+        ValueAt { height } => to_json_binary(&contract::query::value_at(deps, height)?),
+        Changelog { start_after, limit } => to_json_binary(&contract::query::changelog(deps, start_after, limit)?),
+        Donations { start_after, limit, donor } => to_json_binary(&contract::query::donations(deps, start_after, limit, donor)?),
+        Leaderboard { denom, limit } => to_json_binary(&contract::query::leaderboard(deps, denom, limit)?),
+        Referrals { referrer } => to_json_binary(&contract::query::referrals(deps, referrer)?),
+        AccessList { list, start_after, limit } => to_json_binary(&contract::query::access_list(deps, list, start_after, limit)?),
+        Milestones {} => to_json_binary(&contract::query::milestones(deps)?),
     }
 }
 
@@ -46,12 +70,86 @@ pub fn execute(
     use msg::ExecMsg::*;
 
     match msg {
-        Donate {} => contract::exec::donate(deps, env, info).map_err(ContractError::from),
-        Withdraw {} => contract::exec::withdraw(deps, env, info),
+        Donate { campaign: None, memo, beneficiary, referrer } => contract::exec::donate(deps, env, info, memo, beneficiary, referrer),
+        Donate { campaign: Some(campaign), memo, beneficiary, referrer } => contract::exec::donate_campaign(deps, env, info, campaign, memo, beneficiary, referrer),
+        Withdraw { ibc, campaign: None } => contract::exec::withdraw(deps, env, info, ibc),
+        Withdraw { ibc, campaign: Some(campaign) } => contract::exec::withdraw_campaign(deps, env, info, ibc, campaign),
+        Distribute {} => contract::exec::distribute(deps, env, info),
+        Reclaim { donation_id } => contract::exec::reclaim(deps, env, info, donation_id),
+        UpdateWithdrawLimits { limits } => contract::exec::update_withdraw_limits(deps, info, limits),
+        UpdateWithdrawRecipient { recipient } => contract::exec::update_withdraw_recipient(deps, info, recipient),
+        UpdateIbcDestination { channel_id, remote_address } => contract::exec::update_ibc_destination(deps, info, channel_id, remote_address),
+        UpdateIbcCounterparty { connection_id, counterparty } => contract::exec::update_ibc_counterparty(deps, info, connection_id, counterparty),
+        SettleIbcDonation { channel_id, packet_id } => contract::exec::settle_ibc_donation(deps, env, info, channel_id, packet_id),
+        ProposeWithdraw { amount, recipient } => contract::exec::propose(deps, env, info, msg::ProposalAction::Withdraw { amount, recipient }),
+        Propose { action } => contract::exec::propose(deps, env, info, action),
+        Approve { id } => contract::exec::approve(deps, env, info, id),
+        Execute { id } => contract::exec::execute(deps, env, info, id),
+        GrantRole { address, role } => contract::exec::grant_role(deps, info, address, role),
+        RevokeRole { address, role } => contract::exec::revoke_role(deps, info, address, role),
+        Pause {} => contract::exec::set_paused(deps, info, true),
+        Unpause {} => contract::exec::set_paused(deps, info, false),
+        ProposeSpend { amount, recipient, description } => contract::exec::propose_spend(deps, env, info, amount, recipient, description),
+        Vote { id, vote } => contract::exec::vote(deps, env, info, id, vote),
+        ExecuteSpend { id } => contract::exec::execute_spend(deps, env, info, id),
+        UpdateBeneficiaries { beneficiaries } => contract::exec::update_beneficiaries(deps, info, beneficiaries),
+        Delegate { validator, amount } => contract::exec::delegate(deps, env, info, validator, amount),
+        Undelegate { validator, amount } => contract::exec::undelegate(deps, env, info, validator, amount),
+        Redelegate { src_validator, dst_validator, amount } => contract::exec::redelegate(deps, info, src_validator, dst_validator, amount),
+        ClaimRewards {} => contract::exec::claim_rewards(deps, env, info),
+        UpdateSinks { sinks } => contract::exec::update_sinks(deps, info, sinks),
+        UpdateVoucherKey { key } => contract::exec::update_voucher_key(deps, info, key),
+        RedeemVoucher { voucher, signature } => contract::exec::redeem_voucher(deps, env, info, voucher, signature),
+        FundMatchingPool { ratio, donor_cap, deadline } => contract::exec::fund_matching_pool(deps, env, info, ratio, donor_cap, deadline),
+        ReclaimMatchingPool {} => contract::exec::reclaim_matching_pool(deps, env, info),
+        UpdateRoundManager { manager } => contract::exec::update_round_manager(deps, info, manager),
+        CreateCampaign { name, minimal_donation, owner, parent } => contract::exec::create_campaign(deps, info, name, minimal_donation, owner, parent),
+        UpdateCountingMode { mode } => contract::exec::update_counting_mode(deps, info, mode),
+        UpdateReferralPart { part } => contract::exec::update_referral_part(deps, info, part),
+        UpdateAccessMode { mode } => contract::exec::update_access_mode(deps, info, mode),
+        UpdateAccessList { list, add, remove } => contract::exec::update_access_list(deps, info, list, add, remove),
+        UpdateRateLimit { limit } => contract::exec::update_rate_limit(deps, info, limit),
+        AddMilestones { milestones } => contract::exec::add_milestones(deps, info, milestones),
+        RemoveMilestone { threshold } => contract::exec::remove_milestone(deps, info, threshold),
     }
 }
 
 #[cfg_attr(not(feature = "library"),entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
-    contract::migrate(deps, msg)
-}
\ No newline at end of file
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    contract::migrate(deps, env, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    contract::reply(deps, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(deps: DepsMut, _env: Env, msg: IbcChannelOpenMsg) -> Result<IbcChannelOpenResponse, ContractError> {
+    contract::ibc::channel_open(deps, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(deps: DepsMut, _env: Env, msg: IbcChannelConnectMsg) -> Result<IbcBasicResponse, ContractError> {
+    contract::ibc::channel_connect(deps, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(deps: DepsMut, _env: Env, msg: IbcChannelCloseMsg) -> Result<IbcBasicResponse, ContractError> {
+    contract::ibc::channel_close(deps, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(deps: DepsMut, _env: Env, msg: IbcPacketReceiveMsg) -> Result<IbcReceiveResponse, ContractError> {
+    contract::ibc::packet_receive(deps, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(deps: DepsMut, env: Env, msg: IbcPacketAckMsg) -> Result<IbcBasicResponse, ContractError> {
+    contract::ibc::packet_ack(deps, env, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(deps: DepsMut, _env: Env, msg: IbcPacketTimeoutMsg) -> Result<IbcBasicResponse, ContractError> {
+    contract::ibc::packet_timeout(deps, msg)
+}
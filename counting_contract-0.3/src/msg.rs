@@ -1,30 +1,329 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Coin, Decimal};
+use cosmwasm_std::{Addr, Binary, Coin, CosmosMsg, Decimal, Timestamp, Uint128};
+use cw_utils::{Duration, Expiration};
 
 #[cw_serde]
 pub struct Parent {
     pub addr: String,
     pub donating_period: u64,
     pub part: Decimal,
+    pub payout_interval: Option<Duration>,
+    pub keeper_fee: Option<Decimal>,
+    pub ibc: Option<IbcParent>,
 }
 
 #[cw_serde]
+pub struct IbcParent {
+    pub channel_id: String,
+    pub transfer_channel_id: String,
+}
+
+#[cw_serde]
+pub struct IbcCounterparty {
+    pub port_id: String,
+    pub transfer_channel_id: String,
+}
+
+#[cw_serde]
+pub struct Vesting {
+    pub duration: u64,
+    pub cliff: Option<u64>,
+}
+
+#[cw_serde]
+pub struct WithdrawLimits {
+    pub max_amounts: Vec<Coin>,
+    pub window: u64,
+    pub cooldown: u64,
+}
+
+#[cw_serde]
+pub struct DonationWindow {
+    pub duration: Duration,
+    pub max_counted: u64,
+}
+
+#[cw_serde]
+pub enum LimitAction {
+    Reject,
+    Uncount,
+}
+
+#[cw_serde]
+pub struct RateLimit {
+    pub cooldown: Option<Duration>,
+    pub window: Option<DonationWindow>,
+    pub on_exceeded: LimitAction,
+}
+
+#[cw_serde]
+#[derive(Copy)]
+pub enum Role {
+    Owner,
+    Treasurer,
+    ConfigManager,
+    Pauser,
+}
+
+#[cw_serde]
+pub struct Owners {
+    pub members: Vec<String>,
+    pub threshold: u64,
+    pub proposal_duration: Duration,
+}
+
+#[cw_serde]
+pub enum ProposalAction {
+    Withdraw { amount: Vec<Coin>, recipient: String },
+    GrantRole { address: String, role: Role },
+    RevokeRole { address: String, role: Role },
+    UpdateWithdrawRecipient { recipient: Option<String> },
+    Delegate { validator: String, amount: Coin },
+    Undelegate { validator: String, amount: Coin },
+    Redelegate { src_validator: String, dst_validator: String, amount: Coin },
+    UpdateIbcDestination { channel_id: String, remote_address: Option<String> },
+    UpdateIbcCounterparty { connection_id: String, counterparty: Option<IbcCounterparty> },
+    ClaimRewards {},
+    UpdateAccessMode { mode: AccessMode },
+    UpdateAccessList { list: AccessList, add: Vec<String>, remove: Vec<String> },
+    AddMilestones { milestones: Vec<Milestone> },
+    RemoveMilestone { threshold: u64 },
+}
+
+#[cw_serde]
+pub struct Governance {
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub voting_period: Duration,
+}
+
+#[cw_serde]
+#[derive(Copy)]
+pub enum VoteOption {
+    Yes,
+    No,
+    Abstain,
+}
+
+#[cw_serde]
+pub struct Beneficiary {
+    pub address: String,
+    pub weight: Decimal,
+}
+
+#[cw_serde]
+pub enum Sink {
+    CommunityPool { part: Decimal },
+    Burn { part: Decimal },
+}
+
+impl Sink {
+    pub fn part(&self) -> Decimal {
+        match self {
+            Sink::CommunityPool { part } | Sink::Burn { part } => *part,
+        }
+    }
+}
+
+#[cw_serde]
+pub enum CountingMode {
+    Flat,
+    /// Counts one donation per minimal donation sent, up to `cap` and never more than 1000 per call.
+    Proportional { cap: Option<u64> },
+}
+
+#[cw_serde]
+pub enum AccessMode {
+    Open,
+    Allowlist,
+    Blocklist,
+}
+
+#[cw_serde]
+pub enum AccessList {
+    Allowlist,
+    Blocklist,
+}
+
+#[cw_serde]
+pub enum MilestoneAction {
+    Event {},
+    NotifyParent {},
+    Message { msg: CosmosMsg },
+}
+
+#[cw_serde]
+pub struct Milestone {
+    pub threshold: u64,
+    pub action: MilestoneAction,
+}
+
+#[cw_serde]
+pub struct IbcWithdraw {
+    pub channel_id: String,
+    pub remote_address: String,
+    pub timeout: Option<u64>,
+}
+
+#[cw_serde]
+pub enum VoucherKey {
+    Secp256k1(Binary),
+    Ed25519(Binary),
+}
+
+#[cw_serde]
+pub struct Voucher {
+    pub donor: String,
+    pub amount: Coin,
+    pub nonce: u64,
+    pub expiry: Expiration,
+}
+
+#[cw_serde]
+pub struct CampaignParent {
+    pub addr: String,
+    pub donating_period: u64,
+    pub part: Decimal,
+}
+
+#[cw_serde]
+pub struct Unbonding {
+    pub validator: String,
+    pub amount: Coin,
+    pub completes_at: Timestamp,
+}
+
+#[cw_serde]
+#[derive(Default)]
 pub struct InstantiateMsg {
     pub minimal_donation: Coin,
     pub parent: Option<Parent>,
+    pub refund_window: Option<Duration>,
+    pub vesting: Option<Vesting>,
+    pub owners: Option<Owners>,
+    pub governance: Option<Governance>,
+    pub unbonding_period: Option<u64>,
+    pub sinks: Option<Vec<Sink>>,
+    pub ibc_timeout: Option<u64>,
+    pub voucher_key: Option<VoucherKey>,
+    pub counting_mode: Option<CountingMode>,
+    pub referral_part: Option<Decimal>,
+    pub access_mode: Option<AccessMode>,
+    pub rate_limit: Option<RateLimit>,
+    pub milestones: Option<Vec<Milestone>>,
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
     #[returns(ValueResp)]
-    Value {},
+    Value { campaign: Option<String> },
+    #[returns(NextPayoutResp)]
+    NextPayout {},
+    #[returns(VestingResp)]
+    Vesting {},
+    #[returns(WithdrawAllowanceResp)]
+    WithdrawAllowance {},
+    #[returns(OwnersResp)]
+    Owners {},
+    #[returns(ProposalResp)]
+    Proposal { id: u64 },
+    #[returns(RolesResp)]
+    Roles { address: String },
+    #[returns(SpendingProposalResp)]
+    SpendingProposal { id: u64 },
+    #[returns(ContributionsResp)]
+    Contributions { donor: String },
+    #[returns(BeneficiariesResp)]
+    Beneficiaries {},
+    #[returns(StakingResp)]
+    Staking {},
+    #[returns(SinksResp)]
+    Sinks {},
+    #[returns(VoucherResp)]
+    Voucher { nonce: u64 },
+    #[returns(MatchingPoolResp)]
+    MatchingPool {},
+    #[returns(ContributionsResp)]
+    RoundContributions { donor: String },
+    #[returns(CampaignResp)]
+    Campaign { name: String },
+    #[returns(ConfigResp)]
+    Config {},
+    #[returns(ValueResp)]
+    ValueAt { height: u64 },
+    #[returns(ChangelogResp)]
+    Changelog { start_after: Option<u64>, limit: Option<u32> },
+    #[returns(DonationsResp)]
+    Donations { start_after: Option<u64>, limit: Option<u32>, donor: Option<String> },
+    #[returns(LeaderboardResp)]
+    Leaderboard { denom: String, limit: Option<u32> },
+    #[returns(ReferralsResp)]
+    Referrals { referrer: String },
+    #[returns(AccessListResp)]
+    AccessList { list: AccessList, start_after: Option<String>, limit: Option<u32> },
+    #[returns(MilestonesResp)]
+    Milestones {},
 }
 
 #[cw_serde]
 pub enum ExecMsg {
-    Donate {},
-    Withdraw {},
+    Donate {
+        /// Donations to a campaign count once each and are logged, but they are not refundable and do not add to
+        /// contributions, the leaderboard, round totals or the contract counter and its milestones.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        campaign: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        memo: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        beneficiary: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        referrer: Option<String>,
+    },
+    Withdraw { ibc: Option<IbcWithdraw>, campaign: Option<String> },
+    Distribute {},
+    Reclaim { donation_id: u64 },
+    UpdateWithdrawLimits { limits: Option<WithdrawLimits> },
+    UpdateWithdrawRecipient { recipient: Option<String> },
+    UpdateIbcDestination { channel_id: String, remote_address: Option<String> },
+    UpdateIbcCounterparty { connection_id: String, counterparty: Option<IbcCounterparty> },
+    /// Credits the attached funds to the packet, counting its donations once all transferred funds arrived.
+    SettleIbcDonation { channel_id: String, packet_id: u64 },
+    ProposeWithdraw { amount: Vec<Coin>, recipient: String },
+    Propose { action: ProposalAction },
+    Approve { id: u64 },
+    Execute { id: u64 },
+    GrantRole { address: String, role: Role },
+    RevokeRole { address: String, role: Role },
+    Pause {},
+    Unpause {},
+    ProposeSpend { amount: Vec<Coin>, recipient: String, description: String },
+    Vote { id: u64, vote: VoteOption },
+    ExecuteSpend { id: u64 },
+    UpdateBeneficiaries { beneficiaries: Vec<Beneficiary> },
+    Delegate { validator: String, amount: Coin },
+    Undelegate { validator: String, amount: Coin },
+    Redelegate { src_validator: String, dst_validator: String, amount: Coin },
+    ClaimRewards {},
+    UpdateSinks { sinks: Vec<Sink> },
+    UpdateVoucherKey { key: Option<VoucherKey> },
+    RedeemVoucher { voucher: Voucher, signature: Binary },
+    FundMatchingPool { ratio: Decimal, donor_cap: Option<Uint128>, deadline: Expiration },
+    ReclaimMatchingPool {},
+    UpdateRoundManager { manager: Option<String> },
+    CreateCampaign { name: String, minimal_donation: Coin, owner: Option<String>, parent: Option<CampaignParent> },
+    UpdateCountingMode { mode: CountingMode },
+    UpdateReferralPart { part: Decimal },
+    UpdateAccessMode { mode: AccessMode },
+    UpdateAccessList { list: AccessList, add: Vec<String>, remove: Vec<String> },
+    UpdateRateLimit { limit: Option<RateLimit> },
+    AddMilestones { milestones: Vec<Milestone> },
+    RemoveMilestone { threshold: u64 },
+}
+
+#[cw_serde]
+pub enum RoundHookMsg {
+    DonationHook { donor: String, totals: Vec<Coin> },
 }
 
 #[cw_serde]
@@ -32,6 +331,200 @@ pub struct ValueResp {
     pub value: u64,
 }
 
+#[cw_serde]
+pub struct NextPayoutResp {
+    pub donations_left: Option<u64>,
+    pub next_payout: Option<Expiration>,
+    pub due: bool,
+}
+
+#[cw_serde]
+pub struct DenomVesting {
+    pub denom: String,
+    pub vested: Uint128,
+    pub withdrawn: Uint128,
+    pub locked: Uint128,
+}
+
+#[cw_serde]
+pub struct VestingResp {
+    pub denoms: Vec<DenomVesting>,
+}
+
+#[cw_serde]
+pub struct WithdrawAllowanceResp {
+    pub remaining: Vec<Coin>,
+    pub window_resets_at: Option<Timestamp>,
+    pub next_withdraw_at: Option<Timestamp>,
+}
+
+#[cw_serde]
+pub struct OwnersResp {
+    pub members: Vec<Addr>,
+    pub threshold: u64,
+    pub proposal_duration: Duration,
+}
+
+#[cw_serde]
+pub struct ProposalResp {
+    pub id: u64,
+    pub proposer: Addr,
+    pub action: ProposalAction,
+    pub approvals: Vec<Addr>,
+    pub expires: Expiration,
+    pub executed: bool,
+}
+
+#[cw_serde]
+pub struct RolesResp {
+    pub roles: Vec<Role>,
+}
+
+#[cw_serde]
+pub struct SpendingProposalResp {
+    pub id: u64,
+    pub proposer: Addr,
+    pub description: String,
+    pub amount: Vec<Coin>,
+    pub recipient: Addr,
+    pub yes: Uint128,
+    pub no: Uint128,
+    pub abstain: Uint128,
+    pub total_weight: Uint128,
+    pub expires: Expiration,
+    pub executed: bool,
+}
+
+#[cw_serde]
+pub struct ContributionsResp {
+    pub contributions: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct BeneficiariesResp {
+    pub beneficiaries: Vec<Beneficiary>,
+}
+
+#[cw_serde]
+pub struct StakingResp {
+    pub liquid: Vec<Coin>,
+    pub bonded: Vec<Coin>,
+    pub unbonding: Vec<Unbonding>,
+}
+
+#[cw_serde]
+pub struct SinksResp {
+    pub sinks: Vec<Sink>,
+}
+
+#[cw_serde]
+pub struct VoucherResp {
+    pub nonce: u64,
+    pub redeemed_by: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct MatchingPoolResp {
+    pub sponsor: Addr,
+    pub remaining: Coin,
+    pub matched: Uint128,
+    pub ratio: Decimal,
+    pub donor_cap: Option<Uint128>,
+    pub deadline: Expiration,
+}
+
+#[cw_serde]
+pub struct CampaignResp {
+    pub name: String,
+    pub owner: Addr,
+    pub minimal_donation: Coin,
+    pub parent: Option<CampaignParent>,
+    pub counter: u64,
+    pub balance: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct ConfigResp {
+    pub minimal_donation: Coin,
+    pub counting_mode: CountingMode,
+    pub referral_part: Decimal,
+    pub access_mode: AccessMode,
+    pub rate_limit: Option<RateLimit>,
+}
+
+#[cw_serde]
+pub struct CounterChange {
+    pub height: u64,
+    pub value: u64,
+}
+
+#[cw_serde]
+pub struct ChangelogResp {
+    pub changes: Vec<CounterChange>,
+}
+
+#[cw_serde]
+pub struct DonationResp {
+    pub id: u64,
+    pub donor: Addr,
+    pub funds: Vec<Coin>,
+    pub memo: Option<String>,
+    pub campaign: Option<String>,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+#[cw_serde]
+pub struct DonationsResp {
+    pub donations: Vec<DonationResp>,
+}
+
+#[cw_serde]
+pub struct LeaderboardEntry {
+    pub donor: Addr,
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct LeaderboardResp {
+    pub donors: Vec<LeaderboardEntry>,
+}
+
+#[cw_serde]
+pub struct ReferralsResp {
+    pub rewards: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct AccessListResp {
+    pub addresses: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct MilestoneResp {
+    pub threshold: u64,
+    pub action: MilestoneAction,
+    pub reached_at: Option<u64>,
+    pub failed: Option<String>,
+}
+
+#[cw_serde]
+pub struct MilestonesResp {
+    pub reached: Vec<MilestoneResp>,
+    pub pending: Vec<MilestoneResp>,
+}
+
+#[cw_serde]
+pub enum CountingPacket {
+    V1 { id: u64, donations: u64, funds: Vec<Coin> },
+}
+
+#[cw_serde]
+pub enum CountingAck {
+    Result(Binary),
+    Error(String),
+}
+
 #[cw_serde]
 pub struct MigrateMsg {
     pub parent: Option<Parent>,
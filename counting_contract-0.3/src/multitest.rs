@@ -1,11 +1,13 @@
-use cosmwasm_std::{Addr, Coin, StdResult, StdError};
+use cosmwasm_std::{Addr, Coin, Decimal, StdResult, StdError, Uint128};
+use cw_utils::Expiration;
 use cw_multi_test::{App, Executor, ContractWrapper};
 
-use crate::msg::{InstantiateMsg, ValueResp, QueryMsg, ExecMsg, Parent, MigrateMsg};
-use crate::{ContractError, execute, instantiate, query, migrate};
+use crate::msg::{InstantiateMsg, ValueResp, QueryMsg, ExecMsg, Parent, MigrateMsg, NextPayoutResp, VestingResp, WithdrawLimits, WithdrawAllowanceResp, OwnersResp, ProposalAction, ProposalResp, Role, RolesResp, VoteOption, SpendingProposalResp, ContributionsResp, Beneficiary, StakingResp, Sink, SinksResp, MatchingPoolResp, CampaignParent, CampaignResp, CountingMode, ConfigResp};
+use crate::{ContractError, execute, instantiate, query, migrate, reply};
 
 #[cfg(test)]
 mod tests;
+#[derive(Debug)]
 pub struct CountingContract(Addr);
 
 impl CountingContract {
@@ -18,7 +20,7 @@ impl CountingContract {
             execute,
             instantiate,
             query,
-        ).with_migrate(migrate);
+        ).with_migrate(migrate).with_reply(reply);
         app.store_code(Box::new(contract))
     }
 
@@ -31,11 +33,30 @@ impl CountingContract {
         label: &str,
         minimal_donation: Coin,
         parent: Option<Parent>,
-    ) -> StdResult<CountingContract> {
+    ) -> Result<CountingContract, ContractError> {
+        Self::instantiate_with_msg(
+            app,
+            code_id,
+            sender,
+            admin,
+            label,
+            &InstantiateMsg { minimal_donation, parent, ..Default::default() },
+        )
+    }
+
+    #[track_caller]
+    pub fn instantiate_with_msg(
+        app: &mut App, 
+        code_id: u64, 
+        sender: &Addr,
+        admin: Option<&Addr>,
+        label: &str,
+        msg: &InstantiateMsg,
+    ) -> Result<CountingContract, ContractError> {
         app.instantiate_contract(
             code_id, 
             sender.clone(), 
-            &InstantiateMsg { minimal_donation, parent }, &[], 
+            msg, &[], 
             label, 
             admin.map(Addr::to_string),
         )
@@ -45,7 +66,7 @@ impl CountingContract {
 
     #[track_caller]
     pub fn migrate(app: &mut App, sender: &Addr, contract: &Addr, code_id: u64, parent: Option<Parent>) -> StdResult<Self> {
-        app.migrate_contract(sender.clone(), contract.clone(), &MigrateMsg { parent: parent }, code_id)
+        app.migrate_contract(sender.clone(), contract.clone(), &MigrateMsg { parent }, code_id)
             .map_err(|err| err.downcast::<StdError>().unwrap())?;
         Ok(CountingContract(contract.clone()))
     }
@@ -55,7 +76,7 @@ impl CountingContract {
         app.execute_contract(
             sender.clone(), 
             self.0.clone(), 
-            &ExecMsg::Donate {}, 
+            &ExecMsg::Donate { campaign: None, memo: None, beneficiary: None, referrer: None }, 
             funds,
         )
         .map_err(|err| err.downcast::<ContractError>().unwrap())?;
@@ -68,7 +89,379 @@ impl CountingContract {
         app.execute_contract(
             sender.clone(), 
             self.0.clone(), 
-            &ExecMsg::Withdraw {}, 
+            &ExecMsg::Withdraw { ibc: None, campaign: None }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn distribute(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Distribute {}, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn reclaim(&self, app: &mut App, sender: &Addr, donation_id: u64) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Reclaim { donation_id }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn update_withdraw_limits(&self, app: &mut App, sender: &Addr, limits: Option<WithdrawLimits>) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::UpdateWithdrawLimits { limits }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn update_withdraw_recipient(&self, app: &mut App, sender: &Addr, recipient: Option<&Addr>) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::UpdateWithdrawRecipient { recipient: recipient.map(Addr::to_string) }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn propose_withdraw(&self, app: &mut App, sender: &Addr, amount: Vec<Coin>, recipient: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::ProposeWithdraw { amount, recipient: recipient.to_string() }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn propose(&self, app: &mut App, sender: &Addr, action: ProposalAction) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Propose { action }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn approve(&self, app: &mut App, sender: &Addr, id: u64) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Approve { id }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn execute(&self, app: &mut App, sender: &Addr, id: u64) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Execute { id }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn grant_role(&self, app: &mut App, sender: &Addr, address: &Addr, role: Role) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::GrantRole { address: address.to_string(), role }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn revoke_role(&self, app: &mut App, sender: &Addr, address: &Addr, role: Role) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::RevokeRole { address: address.to_string(), role }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn pause(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Pause {}, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn unpause(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Unpause {}, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn propose_spend(&self, app: &mut App, sender: &Addr, amount: Vec<Coin>, recipient: &Addr, description: &str) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::ProposeSpend { amount, recipient: recipient.to_string(), description: description.to_owned() }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn vote(&self, app: &mut App, sender: &Addr, id: u64, vote: VoteOption) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Vote { id, vote }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn execute_spend(&self, app: &mut App, sender: &Addr, id: u64) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::ExecuteSpend { id }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn update_beneficiaries(&self, app: &mut App, sender: &Addr, beneficiaries: Vec<Beneficiary>) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::UpdateBeneficiaries { beneficiaries }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn delegate(&self, app: &mut App, sender: &Addr, validator: &str, amount: Coin) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Delegate { validator: validator.to_string(), amount }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn undelegate(&self, app: &mut App, sender: &Addr, validator: &str, amount: Coin) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Undelegate { validator: validator.to_string(), amount }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn redelegate(&self, app: &mut App, sender: &Addr, src_validator: &str, dst_validator: &str, amount: Coin) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Redelegate { src_validator: src_validator.to_string(), dst_validator: dst_validator.to_string(), amount }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn claim_rewards(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::ClaimRewards {}, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn update_sinks(&self, app: &mut App, sender: &Addr, sinks: Vec<Sink>) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::UpdateSinks { sinks }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn fund_matching_pool(&self, app: &mut App, sender: &Addr, ratio: Decimal, donor_cap: Option<Uint128>, deadline: Expiration, funds: &[Coin]) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::FundMatchingPool { ratio, donor_cap, deadline }, 
+            funds,
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn reclaim_matching_pool(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::ReclaimMatchingPool {}, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn update_round_manager(&self, app: &mut App, sender: &Addr, manager: Option<&Addr>) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::UpdateRoundManager { manager: manager.map(Addr::to_string) }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn create_campaign(
+        &self,
+        app: &mut App,
+        sender: &Addr,
+        name: &str,
+        minimal_donation: Coin,
+        owner: Option<&Addr>,
+        parent: Option<CampaignParent>,
+    ) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::CreateCampaign { name: name.to_owned(), minimal_donation, owner: owner.map(Addr::to_string), parent }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn donate_campaign(&self, app: &mut App, sender: &Addr, campaign: &str, funds: &[Coin]) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Donate { campaign: Some(campaign.to_owned()), memo: None, beneficiary: None, referrer: None }, 
+            funds,
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn withdraw_campaign(&self, app: &mut App, sender: &Addr, campaign: &str) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Withdraw { ibc: None, campaign: Some(campaign.to_owned()) }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn update_counting_mode(&self, app: &mut App, sender: &Addr, mode: CountingMode) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::UpdateCountingMode { mode }, 
             &[],
         )
         .map_err(|err| err.downcast::<ContractError>().unwrap())?;
@@ -78,7 +471,71 @@ impl CountingContract {
 
     pub fn query_value(&self, app: &App) -> StdResult<ValueResp> {
         app.wrap()
-        .query_wasm_smart(self.0.clone(), &QueryMsg::Value {})
+        .query_wasm_smart(self.0.clone(), &QueryMsg::Value { campaign: None })
+    }
+
+    pub fn query_next_payout(&self, app: &App) -> StdResult<NextPayoutResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::NextPayout {})
+    }
+
+    pub fn query_vesting(&self, app: &App) -> StdResult<VestingResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::Vesting {})
+    }
+
+    pub fn query_withdraw_allowance(&self, app: &App) -> StdResult<WithdrawAllowanceResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::WithdrawAllowance {})
+    }
+
+    pub fn query_owners(&self, app: &App) -> StdResult<OwnersResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::Owners {})
+    }
+
+    pub fn query_proposal(&self, app: &App, id: u64) -> StdResult<ProposalResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::Proposal { id })
+    }
+
+    pub fn query_roles(&self, app: &App, address: &Addr) -> StdResult<RolesResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::Roles { address: address.to_string() })
+    }
+
+    pub fn query_spending_proposal(&self, app: &App, id: u64) -> StdResult<SpendingProposalResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::SpendingProposal { id })
+    }
+
+    pub fn query_contributions(&self, app: &App, donor: &Addr) -> StdResult<ContributionsResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::Contributions { donor: donor.to_string() })
+    }
+
+    pub fn query_staking(&self, app: &App) -> StdResult<StakingResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::Staking {})
     }
 
-}
\ No newline at end of file
+    pub fn query_sinks(&self, app: &App) -> StdResult<SinksResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::Sinks {})
+    }
+
+    pub fn query_matching_pool(&self, app: &App) -> StdResult<MatchingPoolResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::MatchingPool {})
+    }
+
+    pub fn query_campaign(&self, app: &App, name: &str) -> StdResult<CampaignResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::Campaign { name: name.to_owned() })
+    }
+
+    pub fn query_config(&self, app: &App) -> StdResult<ConfigResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::Config {})
+    }
+}
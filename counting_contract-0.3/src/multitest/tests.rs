@@ -1,10 +1,12 @@
-use cosmwasm_std::{Addr, Coin, Empty, coins, Decimal};
-use cw_multi_test::{App, Contract, ContractWrapper};
+use cosmwasm_std::{Addr, Coin, Empty, coins, Decimal, Uint128, Validator};
+use cosmwasm_std::testing::mock_env;
+use cw_utils::{Duration, Expiration};
+use cw_multi_test::{App, Contract, ContractWrapper, Executor, StakingInfo, StakingSudo, SudoMsg};
 
-use crate::msg::Parent;
+use crate::msg::{Parent, InstantiateMsg, Vesting, DenomVesting, WithdrawLimits, Owners, ProposalAction, Role, Governance, VoteOption, Beneficiary, Unbonding, Sink, MatchingPoolResp, CampaignParent, CountingMode, AccessMode};
 use crate::{execute, instantiate, query, multitest::CountingContract, error::ContractError};
 use crate::state::{STATE, State};
-use counting_contract_0_1::multitest::CountingContract as CountingContract_0_1_0;
+use counting_contract_0_1::msg::{InstantiateMsg as InstantiateMsg_0_1_0, ExecMsg as ExecMsg_0_1_0};
 
 fn counting_contract() -> Box<dyn Contract<Empty>> {
     let contract = ContractWrapper::new(
@@ -15,6 +17,15 @@ fn counting_contract() -> Box<dyn Contract<Empty>> {
     Box::new(contract)
 }
 
+fn counting_contract_0_1_0() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(
+        counting_contract_0_1::execute,
+        counting_contract_0_1::instantiate,
+        counting_contract_0_1::query,
+    );
+    Box::new(contract)
+}
+
 const ATOM: &str = "atom";
 
 #[test]
@@ -152,7 +163,7 @@ fn unauthorized_withdraw() {
 
     assert_eq!(
         err,
-        ContractError::Unauthorized {owner: owner.into()}
+        ContractError::Unauthorized { required_role: Role::Treasurer }
     );
 
 }
@@ -170,23 +181,23 @@ fn migration() {
             .unwrap();
     });
 
-    let old_code_id = CountingContract_0_1_0::store_code(&mut app);
+    let old_code_id = app.store_code(counting_contract_0_1_0());
     let new_code_id = CountingContract::store_code(&mut app);
 
-    let contract = CountingContract_0_1_0::instantiate(
-        &mut app, 
+    let contract = app.instantiate_contract(
         old_code_id,
-        &owner, 
-        Some(&admin),
+        owner.clone(), 
+        &InstantiateMsg_0_1_0 { minimal_donation: Coin::new(10, ATOM) },
+        &[],
         "Counting contract", 
-        Coin::new(10, ATOM),
+        Some(admin.to_string()),
     ).unwrap();
 
-    contract
-        .donate(&mut app, &sender, &coins(10, ATOM))
+    app
+        .execute_contract(sender.clone(), contract.clone(), &ExecMsg_0_1_0::Donate {}, &coins(10, ATOM))
         .unwrap();
     
-    let contract = CountingContract::migrate(&mut app, &admin, contract.addr(), new_code_id, None).unwrap();
+    let contract = CountingContract::migrate(&mut app, &admin, &contract, new_code_id, None).unwrap();
 
     let resp = contract.query_value(&app).unwrap();
     assert_eq!(resp.value, 1);
@@ -195,11 +206,14 @@ fn migration() {
     assert_eq!(
         state,
         State {
-            counter: 1,
             minimal_donation: Coin::new(10, ATOM),
             donating_parent: None,
         }
     );
+
+    let owners = contract.query_owners(&app).unwrap();
+    assert_eq!(owners.members, vec![owner]);
+    assert_eq!(owners.threshold, 1);
 }
 
 #[test]
@@ -258,6 +272,9 @@ fn donating_parent() {
             addr: contract_parent.addr().to_string(),
             donating_period: 2,
             part: Decimal::percent(10),
+            payout_interval: None,
+            keeper_fee: None,
+            ibc: None,
         }),
     ).unwrap();
 
@@ -275,4 +292,1390 @@ fn donating_parent() {
     assert_eq!(app.wrap().query_all_balances(contract_parent.addr()).unwrap(), coins(2, ATOM));
     assert_eq!(app.wrap().query_all_balances(owner.clone()).unwrap(), vec![]);
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn donating_parent_rejected() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract_parent = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            access_mode: Some(AccessMode::Allowlist),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let contract = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        Some(Parent {
+            addr: contract_parent.addr().to_string(),
+            donating_period: 1,
+            part: Decimal::percent(10),
+            payout_interval: None,
+            keeper_fee: None,
+            ibc: None,
+        }),
+    ).unwrap();
+
+    contract.donate(&mut app, &sender, &coins(10, ATOM)).unwrap();
+
+    assert_eq!(contract.query_value(&app).unwrap().value, 1);
+    assert_eq!(contract_parent.query_value(&app).unwrap().value, 0);
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), coins(10, ATOM));
+    assert_eq!(app.wrap().query_all_balances(contract_parent.addr()).unwrap(), vec![]);
+}
+
+#[test]
+fn donating_parent_by_time() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract_parent = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        None,
+    ).unwrap();
+
+    let contract = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        Some(Parent {
+            addr: contract_parent.addr().to_string(),
+            donating_period: 2,
+            part: Decimal::percent(10),
+            payout_interval: Some(Duration::Time(100)),
+            keeper_fee: None,
+            ibc: None,
+        }),
+    ).unwrap();
+
+    let start = app.block_info().time;
+    let resp = contract.query_next_payout(&app).unwrap();
+    assert_eq!(resp.donations_left, None);
+    assert_eq!(resp.next_payout, Some(Expiration::AtTime(start.plus_seconds(100))));
+
+    contract.donate(&mut app, &sender, &coins(10, ATOM)).unwrap();
+
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), coins(10, ATOM));
+    assert_eq!(app.wrap().query_all_balances(contract_parent.addr()).unwrap(), vec![]);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    contract.donate(&mut app, &sender, &coins(10, ATOM)).unwrap();
+
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), coins(18, ATOM));
+    assert_eq!(app.wrap().query_all_balances(contract_parent.addr()).unwrap(), coins(2, ATOM));
+
+    let resp = contract.query_next_payout(&app).unwrap();
+    assert_eq!(resp.next_payout, Some(Expiration::AtTime(start.plus_seconds(200))));
+}
+
+#[test]
+fn distribute() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+    let keeper = Addr::unchecked("keeper");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract_parent = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        None,
+    ).unwrap();
+
+    let contract = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        Some(Parent {
+            addr: contract_parent.addr().to_string(),
+            donating_period: 2,
+            part: Decimal::percent(10),
+            payout_interval: Some(Duration::Height(10)),
+            keeper_fee: None,
+            ibc: None,
+        }),
+    ).unwrap();
+
+    contract.donate(&mut app, &sender, &coins(10, ATOM)).unwrap();
+
+    let height = app.block_info().height;
+    let err = contract.distribute(&mut app, &keeper).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::PayoutNotDue { next_payout: Expiration::AtHeight(height + 10) }
+    );
+
+    app.update_block(|block| block.height += 10);
+
+    contract.distribute(&mut app, &keeper).unwrap();
+
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), coins(9, ATOM));
+    assert_eq!(app.wrap().query_all_balances(contract_parent.addr()).unwrap(), coins(1, ATOM));
+}
+
+#[test]
+fn distribute_with_keeper_fee() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+    let keeper = Addr::unchecked("keeper");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(1000, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract_parent = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        None,
+    ).unwrap();
+
+    let contract = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        Some(Parent {
+            addr: contract_parent.addr().to_string(),
+            donating_period: 1,
+            part: Decimal::percent(10),
+            payout_interval: None,
+            keeper_fee: Some(Decimal::percent(10)),
+            ibc: None,
+        }),
+    ).unwrap();
+
+    contract.donate(&mut app, &sender, &coins(1000, ATOM)).unwrap();
+
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), coins(1000, ATOM));
+    assert!(contract.query_next_payout(&app).unwrap().due);
+
+    contract.distribute(&mut app, &keeper).unwrap();
+
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), coins(900, ATOM));
+    assert_eq!(app.wrap().query_all_balances(contract_parent.addr()).unwrap(), coins(90, ATOM));
+    assert_eq!(app.wrap().query_all_balances(&keeper).unwrap(), coins(10, ATOM));
+    assert!(!contract.query_next_payout(&app).unwrap().due);
+
+    let err = contract.distribute(&mut app, &keeper).unwrap_err();
+    assert_eq!(err, ContractError::PayoutNotDue { next_payout: Expiration::Never {} });
+}
+
+#[test]
+fn invalid_keeper_fee() {
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::default();
+    let code_id = CountingContract::store_code(&mut app);
+
+    let err = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        Some(Parent {
+            addr: "parent".to_owned(),
+            donating_period: 1,
+            part: Decimal::percent(10),
+            payout_interval: None,
+            keeper_fee: Some(Decimal::percent(110)),
+            ibc: None,
+        }),
+    ).unwrap_err();
+
+    assert_eq!(err, ContractError::InvalidKeeperFee(Decimal::percent(110)));
+}
+
+#[test]
+fn reclaim() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            refund_window: Some(Duration::Height(10)),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    contract.donate(&mut app, &sender, &coins(10, ATOM)).unwrap();
+    assert_eq!(contract.query_value(&app).unwrap().value, 1);
+
+    let err = contract.reclaim(&mut app, &owner, 1).unwrap_err();
+    assert_eq!(err, ContractError::NotDonor { donation_id: 1 });
+
+    contract.withdraw(&mut app, &owner).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), vec![]);
+
+    contract.reclaim(&mut app, &sender, 1).unwrap();
+
+    assert_eq!(contract.query_value(&app).unwrap().value, 0);
+    assert_eq!(app.wrap().query_all_balances(&sender).unwrap(), coins(10, ATOM));
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), vec![]);
+}
+
+#[test]
+fn reclaim_after_refund_window() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(25, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            refund_window: Some(Duration::Time(100)),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    contract.donate(&mut app, &sender, &coins(10, ATOM)).unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(50));
+    contract.donate(&mut app, &sender, &coins(10, ATOM)).unwrap();
+    contract.donate(&mut app, &sender, &coins(5, ATOM)).unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(50));
+
+    let err = contract.reclaim(&mut app, &sender, 1).unwrap_err();
+    assert_eq!(err, ContractError::RefundWindowExpired { donation_id: 1 });
+
+    contract.withdraw(&mut app, &owner).unwrap();
+
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), coins(15, ATOM));
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), coins(10, ATOM));
+
+    contract.reclaim(&mut app, &sender, 2).unwrap();
+    contract.reclaim(&mut app, &sender, 3).unwrap_err();
+
+    assert_eq!(contract.query_value(&app).unwrap().value, 1);
+    assert_eq!(app.wrap().query_all_balances(&sender).unwrap(), coins(10, ATOM));
+}
+
+#[test]
+fn withdraw_vested() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(100, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            vesting: Some(Vesting { duration: 100, cliff: Some(20) }),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    contract.donate(&mut app, &sender, &coins(100, ATOM)).unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(10));
+    contract.withdraw(&mut app, &owner).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), vec![]);
+
+    app.update_block(|block| block.time = block.time.plus_seconds(40));
+    contract.withdraw(&mut app, &owner).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), coins(50, ATOM));
+
+    let resp = contract.query_vesting(&app).unwrap();
+    assert_eq!(
+        resp.denoms,
+        vec![DenomVesting {
+            denom: ATOM.to_owned(),
+            vested: Uint128::new(50),
+            withdrawn: Uint128::new(50),
+            locked: Uint128::new(50),
+        }]
+    );
+
+    app.update_block(|block| block.time = block.time.plus_seconds(50));
+    contract.withdraw(&mut app, &owner).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), coins(100, ATOM));
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), vec![]);
+}
+
+#[test]
+fn withdraw_limits() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(100, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        None,
+    ).unwrap();
+
+    let limits = WithdrawLimits {
+        max_amounts: coins(30, ATOM),
+        window: 100,
+        cooldown: 10,
+    };
+
+    let err = contract.update_withdraw_limits(&mut app, &sender, Some(limits.clone())).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { required_role: Role::ConfigManager });
+
+    contract.update_withdraw_limits(&mut app, &owner, Some(limits)).unwrap();
+    contract.withdraw(&mut app, &owner).unwrap();
+    assert_eq!(contract.query_withdraw_allowance(&app).unwrap().next_withdraw_at, None);
+
+    contract.donate(&mut app, &sender, &coins(100, ATOM)).unwrap();
+
+    let start = app.block_info().time;
+    contract.withdraw(&mut app, &owner).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), coins(30, ATOM));
+
+    let err = contract.withdraw(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::WithdrawLimitExceeded { available_at: start.plus_seconds(10) });
+
+    app.update_block(|block| block.time = block.time.plus_seconds(10));
+    let err = contract.withdraw(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::WithdrawLimitExceeded { available_at: start.plus_seconds(100) });
+
+    let resp = contract.query_withdraw_allowance(&app).unwrap();
+    assert_eq!(resp.remaining, coins(0, ATOM));
+    assert_eq!(resp.window_resets_at, Some(start.plus_seconds(100)));
+
+    app.update_block(|block| block.time = block.time.plus_seconds(90));
+    contract.withdraw(&mut app, &owner).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), coins(60, ATOM));
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), coins(40, ATOM));
+}
+
+#[test]
+fn multisig_withdraw() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let carol = Addr::unchecked("carol");
+    let recipient = Addr::unchecked("recipient");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(100, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &alice, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            owners: Some(Owners {
+                members: vec![alice.to_string(), bob.to_string(), carol.to_string()],
+                threshold: 2,
+                proposal_duration: Duration::Time(100),
+            }),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    contract.donate(&mut app, &sender, &coins(100, ATOM)).unwrap();
+
+    let err = contract.withdraw(&mut app, &alice).unwrap_err();
+    assert_eq!(err, ContractError::ProposalRequired { threshold: 2 });
+
+    let err = contract.propose_withdraw(&mut app, &sender, coins(40, ATOM), &recipient).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { required_role: Role::Owner });
+
+    contract.propose_withdraw(&mut app, &alice, coins(40, ATOM), &recipient).unwrap();
+
+    let err = contract.execute(&mut app, &alice, 1).unwrap_err();
+    assert_eq!(err, ContractError::ThresholdNotReached { proposal_id: 1 });
+
+    let err = contract.approve(&mut app, &alice, 1).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyApproved { proposal_id: 1 });
+
+    contract.approve(&mut app, &bob, 1).unwrap();
+    contract.execute(&mut app, &sender, 1).unwrap();
+
+    assert_eq!(app.wrap().query_all_balances(&recipient).unwrap(), coins(40, ATOM));
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), coins(60, ATOM));
+    assert!(contract.query_proposal(&app, 1).unwrap().executed);
+
+    let err = contract.execute(&mut app, &sender, 1).unwrap_err();
+    assert_eq!(err, ContractError::ProposalExecuted { proposal_id: 1 });
+
+    contract.propose_withdraw(&mut app, &bob, coins(60, ATOM), &recipient).unwrap();
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    let err = contract.approve(&mut app, &carol, 2).unwrap_err();
+    assert_eq!(err, ContractError::ProposalExpired { proposal_id: 2 });
+}
+
+#[test]
+fn multisig_owner_change() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let carol = Addr::unchecked("carol");
+    let mallory = Addr::unchecked("mallory");
+
+    let mut app = App::default();
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &alice, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            owners: Some(Owners {
+                members: vec![alice.to_string(), bob.to_string(), carol.to_string()],
+                threshold: 2,
+                proposal_duration: Duration::Time(100),
+            }),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let err = contract.grant_role(&mut app, &alice, &mallory, Role::Owner).unwrap_err();
+    assert_eq!(err, ContractError::ProposalRequired { threshold: 2 });
+
+    let err = contract.revoke_role(&mut app, &alice, &bob, Role::Owner).unwrap_err();
+    assert_eq!(err, ContractError::ProposalRequired { threshold: 2 });
+
+    contract.propose(&mut app, &alice, ProposalAction::GrantRole { address: mallory.to_string(), role: Role::Owner }).unwrap();
+    let err = contract.execute(&mut app, &alice, 1).unwrap_err();
+    assert_eq!(err, ContractError::ThresholdNotReached { proposal_id: 1 });
+
+    contract.approve(&mut app, &bob, 1).unwrap();
+    contract.execute(&mut app, &alice, 1).unwrap();
+    assert_eq!(contract.query_owners(&app).unwrap().members, vec![alice.clone(), bob.clone(), carol.clone(), mallory.clone()]);
+
+    contract.propose(&mut app, &bob, ProposalAction::RevokeRole { address: mallory.to_string(), role: Role::Owner }).unwrap();
+    contract.approve(&mut app, &carol, 2).unwrap();
+    contract.execute(&mut app, &bob, 2).unwrap();
+    assert_eq!(contract.query_owners(&app).unwrap().members, vec![alice, bob, carol]);
+}
+
+#[test]
+fn multisig_role_and_config_change() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let treasurer = Addr::unchecked("treasurer");
+
+    let mut app = App::default();
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &alice, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            owners: Some(Owners {
+                members: vec![alice.to_string(), bob.to_string()],
+                threshold: 2,
+                proposal_duration: Duration::Time(100),
+            }),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let err = contract.grant_role(&mut app, &alice, &treasurer, Role::Treasurer).unwrap_err();
+    assert_eq!(err, ContractError::ProposalRequired { threshold: 2 });
+
+    let err = contract.update_withdraw_recipient(&mut app, &alice, Some(&treasurer)).unwrap_err();
+    assert_eq!(err, ContractError::ProposalRequired { threshold: 2 });
+
+    let err = contract.claim_rewards(&mut app, &alice).unwrap_err();
+    assert_eq!(err, ContractError::ProposalRequired { threshold: 2 });
+
+    contract.propose(&mut app, &alice, ProposalAction::GrantRole { address: treasurer.to_string(), role: Role::Treasurer }).unwrap();
+    contract.approve(&mut app, &bob, 1).unwrap();
+    contract.execute(&mut app, &alice, 1).unwrap();
+    assert_eq!(contract.query_roles(&app, &treasurer).unwrap().roles, vec![Role::Treasurer]);
+
+    let err = contract.revoke_role(&mut app, &alice, &treasurer, Role::Treasurer).unwrap_err();
+    assert_eq!(err, ContractError::ProposalRequired { threshold: 2 });
+
+    contract.propose(&mut app, &bob, ProposalAction::RevokeRole { address: treasurer.to_string(), role: Role::Treasurer }).unwrap();
+    contract.approve(&mut app, &alice, 2).unwrap();
+    contract.execute(&mut app, &bob, 2).unwrap();
+    assert_eq!(contract.query_roles(&app, &treasurer).unwrap().roles, vec![]);
+}
+
+#[test]
+fn multisig_invalid_threshold() {
+    let owner = Addr::unchecked("owner");
+
+    let mut app = App::default();
+    let code_id = CountingContract::store_code(&mut app);
+
+    let err = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            owners: Some(Owners {
+                members: vec![owner.to_string()],
+                threshold: 2,
+                proposal_duration: Duration::Time(100),
+            }),
+            ..Default::default()
+        },
+    ).unwrap_err();
+
+    assert_eq!(err, ContractError::InvalidThreshold { threshold: 2, members: 1 });
+}
+
+#[test]
+fn roles() {
+    let owner = Addr::unchecked("owner");
+    let treasurer = Addr::unchecked("treasurer");
+    let recipient = Addr::unchecked("recipient");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(20, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        None,
+    ).unwrap();
+
+    let resp = contract.query_roles(&app, &owner).unwrap();
+    assert_eq!(resp.roles, vec![Role::Owner, Role::Treasurer, Role::ConfigManager, Role::Pauser]);
+
+    let err = contract.grant_role(&mut app, &treasurer, &treasurer, Role::Treasurer).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { required_role: Role::Owner });
+
+    contract.grant_role(&mut app, &owner, &treasurer, Role::Treasurer).unwrap();
+    assert_eq!(contract.query_roles(&app, &treasurer).unwrap().roles, vec![Role::Treasurer]);
+
+    contract.donate(&mut app, &sender, &coins(10, ATOM)).unwrap();
+    contract.withdraw(&mut app, &treasurer).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&treasurer).unwrap(), vec![]);
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), coins(10, ATOM));
+
+    let err = contract.update_withdraw_recipient(&mut app, &treasurer, Some(&treasurer)).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { required_role: Role::Owner });
+
+    contract.update_withdraw_recipient(&mut app, &owner, Some(&recipient)).unwrap();
+    contract.donate(&mut app, &sender, &coins(10, ATOM)).unwrap();
+    contract.withdraw(&mut app, &treasurer).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&recipient).unwrap(), coins(10, ATOM));
+
+    contract.revoke_role(&mut app, &owner, &treasurer, Role::Treasurer).unwrap();
+    assert_eq!(contract.query_roles(&app, &treasurer).unwrap().roles, vec![]);
+
+    let err = contract.withdraw(&mut app, &treasurer).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { required_role: Role::Treasurer });
+}
+
+#[test]
+fn pause() {
+    let owner = Addr::unchecked("owner");
+    let pauser = Addr::unchecked("pauser");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        None,
+    ).unwrap();
+
+    let err = contract.pause(&mut app, &pauser).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { required_role: Role::Pauser });
+
+    contract.grant_role(&mut app, &owner, &pauser, Role::Pauser).unwrap();
+    contract.pause(&mut app, &pauser).unwrap();
+
+    let err = contract.donate(&mut app, &sender, &coins(10, ATOM)).unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+
+    contract.unpause(&mut app, &pauser).unwrap();
+    contract.donate(&mut app, &sender, &coins(10, ATOM)).unwrap();
+    assert_eq!(contract.query_value(&app).unwrap().value, 1);
+}
+
+#[test]
+fn donor_governance() {
+    let owner = Addr::unchecked("owner");
+    let donor1 = Addr::unchecked("donor1");
+    let donor2 = Addr::unchecked("donor2");
+    let stranger = Addr::unchecked("stranger");
+    let recipient = Addr::unchecked("recipient");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &donor1, coins(60, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &donor2, coins(40, ATOM))
+            .unwrap();
+        router
+            .bank
+            .init_balance(storage, &stranger, coins(10, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            governance: Some(Governance {
+                quorum: Decimal::percent(50),
+                threshold: Decimal::percent(50),
+                voting_period: Duration::Time(100),
+            }),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    contract.donate(&mut app, &donor1, &coins(60, ATOM)).unwrap();
+    contract.donate(&mut app, &donor2, &coins(40, ATOM)).unwrap();
+    assert_eq!(contract.query_contributions(&app, &donor1).unwrap().contributions, coins(60, ATOM));
+
+    let err = contract.withdraw(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::SpendingProposalRequired {});
+
+    app.update_block(|block| block.height += 1);
+    contract.propose_spend(&mut app, &owner, coins(50, ATOM), &recipient, "community event").unwrap();
+    assert_eq!(contract.query_spending_proposal(&app, 1).unwrap().total_weight, Uint128::new(100));
+
+    contract.vote(&mut app, &donor1, 1, VoteOption::Yes).unwrap();
+
+    let err = contract.vote(&mut app, &donor1, 1, VoteOption::Yes).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyVoted { proposal_id: 1 });
+
+    app.update_block(|block| block.height += 1);
+    contract.donate(&mut app, &stranger, &coins(10, ATOM)).unwrap();
+    let err = contract.vote(&mut app, &stranger, 1, VoteOption::Yes).unwrap_err();
+    assert_eq!(err, ContractError::NoVotingPower {});
+
+    let err = contract.execute_spend(&mut app, &owner, 1).unwrap_err();
+    assert_eq!(err, ContractError::VotingOpen { proposal_id: 1 });
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    let err = contract.vote(&mut app, &donor2, 1, VoteOption::No).unwrap_err();
+    assert_eq!(err, ContractError::VotingClosed { proposal_id: 1 });
+
+    contract.execute_spend(&mut app, &owner, 1).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&recipient).unwrap(), coins(50, ATOM));
+    assert!(contract.query_spending_proposal(&app, 1).unwrap().executed);
+
+    app.update_block(|block| block.height += 1);
+    contract.propose_spend(&mut app, &owner, coins(50, ATOM), &recipient, "second event").unwrap();
+    contract.vote(&mut app, &donor1, 2, VoteOption::No).unwrap();
+    contract.vote(&mut app, &donor2, 2, VoteOption::Yes).unwrap();
+
+    app.update_block(|block| block.time = block.time.plus_seconds(100));
+
+    let err = contract.execute_spend(&mut app, &owner, 2).unwrap_err();
+    assert_eq!(err, ContractError::ProposalRejected { proposal_id: 2 });
+}
+
+#[test]
+fn withdraw_to_beneficiaries() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let carol = Addr::unchecked("carol");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(101, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        None,
+    ).unwrap();
+
+    let err = contract.update_beneficiaries(&mut app, &owner, vec![
+        Beneficiary { address: alice.to_string(), weight: Decimal::percent(50) },
+        Beneficiary { address: bob.to_string(), weight: Decimal::percent(30) },
+    ]).unwrap_err();
+    assert_eq!(err, ContractError::InvalidWeights { total: Decimal::percent(80) });
+
+    contract.update_beneficiaries(&mut app, &owner, vec![
+        Beneficiary { address: alice.to_string(), weight: Decimal::percent(50) },
+        Beneficiary { address: bob.to_string(), weight: Decimal::percent(30) },
+        Beneficiary { address: carol.to_string(), weight: Decimal::percent(20) },
+    ]).unwrap();
+
+    contract.donate(&mut app, &sender, &coins(101, ATOM)).unwrap();
+    contract.withdraw(&mut app, &owner).unwrap();
+
+    assert_eq!(app.wrap().query_all_balances(&alice).unwrap(), coins(51, ATOM));
+    assert_eq!(app.wrap().query_all_balances(&bob).unwrap(), coins(30, ATOM));
+    assert_eq!(app.wrap().query_all_balances(&carol).unwrap(), coins(20, ATOM));
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), vec![]);
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), vec![]);
+}
+
+#[test]
+fn staking() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, api, storage| {
+        router
+            .staking
+            .setup(storage, StakingInfo {
+                bonded_denom: ATOM.to_owned(),
+                unbonding_time: 60,
+                apr: Decimal::percent(10),
+            })
+            .unwrap();
+        for validator in ["validator1", "validator2"] {
+            router
+                .staking
+                .add_validator(api, storage, &mock_env().block, Validator {
+                    address: validator.to_owned(),
+                    commission: Decimal::percent(10),
+                    max_commission: Decimal::percent(20),
+                    max_change_rate: Decimal::percent(1),
+                })
+                .unwrap();
+        }
+        router
+            .bank
+            .init_balance(storage, &sender, coins(1000, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            unbonding_period: Some(60),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    contract.donate(&mut app, &sender, &coins(1000, ATOM)).unwrap();
+
+    let err = contract.delegate(&mut app, &sender, "validator1", Coin::new(600, ATOM)).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { required_role: Role::Owner });
+
+    let err = contract.delegate(&mut app, &owner, "validator1", Coin::new(1500, ATOM)).unwrap_err();
+    assert_eq!(err, ContractError::InsufficientFunds {
+        requested: Coin::new(1500, ATOM),
+        available: Coin::new(1000, ATOM),
+    });
+
+    contract.delegate(&mut app, &owner, "validator1", Coin::new(600, ATOM)).unwrap();
+    contract.withdraw(&mut app, &owner).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), coins(400, ATOM));
+
+    contract.redelegate(&mut app, &owner, "validator1", "validator2", Coin::new(200, ATOM)).unwrap();
+    contract.undelegate(&mut app, &owner, "validator1", Coin::new(300, ATOM)).unwrap();
+
+    let resp = contract.query_staking(&app).unwrap();
+    assert_eq!(resp.liquid, vec![]);
+    assert_eq!(resp.bonded, coins(300, ATOM));
+    assert_eq!(resp.unbonding, vec![Unbonding {
+        validator: "validator1".to_owned(),
+        amount: Coin::new(300, ATOM),
+        completes_at: app.block_info().time.plus_seconds(60),
+    }]);
+
+    let resp = contract.query_vesting(&app).unwrap();
+    assert_eq!(
+        resp.denoms,
+        vec![DenomVesting {
+            denom: ATOM.to_owned(),
+            vested: Uint128::new(1000),
+            withdrawn: Uint128::new(400),
+            locked: Uint128::zero(),
+        }]
+    );
+
+    app.update_block(|block| block.time = block.time.plus_seconds(60));
+    app.sudo(SudoMsg::Staking(StakingSudo::ProcessQueue {})).unwrap();
+
+    let resp = contract.query_staking(&app).unwrap();
+    assert_eq!(resp.liquid, coins(300, ATOM));
+    assert_eq!(resp.unbonding, vec![]);
+
+    contract.withdraw(&mut app, &owner).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), coins(700, ATOM));
+
+    app.update_block(|block| block.time = block.time.plus_seconds(365 * 24 * 60 * 60));
+    contract.claim_rewards(&mut app, &owner).unwrap();
+    assert!(!app.wrap().query_balance(contract.addr(), ATOM).unwrap().amount.is_zero());
+}
+
+#[test]
+fn multisig_staking() {
+    let alice = Addr::unchecked("alice");
+    let bob = Addr::unchecked("bob");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, api, storage| {
+        router
+            .staking
+            .setup(storage, StakingInfo {
+                bonded_denom: ATOM.to_owned(),
+                unbonding_time: 60,
+                apr: Decimal::percent(10),
+            })
+            .unwrap();
+        for validator in ["validator1", "validator2"] {
+            router
+                .staking
+                .add_validator(api, storage, &mock_env().block, Validator {
+                    address: validator.to_owned(),
+                    commission: Decimal::percent(10),
+                    max_commission: Decimal::percent(20),
+                    max_change_rate: Decimal::percent(1),
+                })
+                .unwrap();
+        }
+        router
+            .bank
+            .init_balance(storage, &sender, coins(1000, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &alice, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            owners: Some(Owners {
+                members: vec![alice.to_string(), bob.to_string()],
+                threshold: 2,
+                proposal_duration: Duration::Time(100),
+            }),
+            unbonding_period: Some(60),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    contract.donate(&mut app, &sender, &coins(1000, ATOM)).unwrap();
+
+    let err = contract.delegate(&mut app, &alice, "validator1", Coin::new(600, ATOM)).unwrap_err();
+    assert_eq!(err, ContractError::ProposalRequired { threshold: 2 });
+
+    let err = contract.redelegate(&mut app, &alice, "validator1", "validator2", Coin::new(200, ATOM)).unwrap_err();
+    assert_eq!(err, ContractError::ProposalRequired { threshold: 2 });
+
+    let err = contract.undelegate(&mut app, &alice, "validator1", Coin::new(300, ATOM)).unwrap_err();
+    assert_eq!(err, ContractError::ProposalRequired { threshold: 2 });
+
+    contract.propose(&mut app, &alice, ProposalAction::Delegate { validator: "validator1".to_owned(), amount: Coin::new(600, ATOM) }).unwrap();
+    contract.approve(&mut app, &bob, 1).unwrap();
+    contract.execute(&mut app, &alice, 1).unwrap();
+
+    contract.propose(&mut app, &bob, ProposalAction::Undelegate { validator: "validator1".to_owned(), amount: Coin::new(300, ATOM) }).unwrap();
+    contract.approve(&mut app, &alice, 2).unwrap();
+    contract.execute(&mut app, &bob, 2).unwrap();
+
+    let resp = contract.query_staking(&app).unwrap();
+    assert_eq!(resp.liquid, coins(400, ATOM));
+    assert_eq!(resp.bonded, coins(300, ATOM));
+    assert_eq!(resp.unbonding, vec![Unbonding {
+        validator: "validator1".to_owned(),
+        amount: Coin::new(300, ATOM),
+        completes_at: app.block_info().time.plus_seconds(60),
+    }]);
+}
+
+#[test]
+fn burn_sink() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &sender, coins(105, ATOM))
+            .unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let err = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            sinks: Some(vec![
+                Sink::Burn { part: Decimal::percent(60) },
+                Sink::CommunityPool { part: Decimal::percent(50) },
+            ]),
+            ..Default::default()
+        },
+    ).unwrap_err();
+    assert_eq!(err, ContractError::InvalidSinkShares { total: Decimal::percent(110) });
+
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            sinks: Some(vec![Sink::Burn { part: Decimal::percent(10) }]),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    contract.donate(&mut app, &sender, &coins(100, ATOM)).unwrap();
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), coins(90, ATOM));
+
+    contract.donate(&mut app, &sender, &coins(5, ATOM)).unwrap();
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), coins(95, ATOM));
+    assert_eq!(contract.query_contributions(&app, &sender).unwrap().contributions, coins(95, ATOM));
+
+    let err = contract.update_sinks(&mut app, &sender, vec![]).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { required_role: Role::ConfigManager });
+
+    contract.update_sinks(&mut app, &owner, vec![]).unwrap();
+    assert_eq!(contract.query_sinks(&app).unwrap().sinks, vec![]);
+}
+
+#[test]
+fn matching_pool() {
+    let owner = Addr::unchecked("owner");
+    let sponsor = Addr::unchecked("sponsor");
+    let sender1 = Addr::unchecked("sender1");
+    let sender2 = Addr::unchecked("sender2");
+    let sender3 = Addr::unchecked("sender3");
+
+    let mut app = App::new(|router, _api, storage| {
+        router.bank.init_balance(storage, &sponsor, coins(40, ATOM)).unwrap();
+        router.bank.init_balance(storage, &sender1, coins(40, ATOM)).unwrap();
+        router.bank.init_balance(storage, &sender2, coins(80, ATOM)).unwrap();
+        router.bank.init_balance(storage, &sender3, coins(40, ATOM)).unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        None,
+    ).unwrap();
+
+    let deadline = Expiration::AtHeight(app.block_info().height + 10);
+    contract.fund_matching_pool(&mut app, &sponsor, Decimal::percent(50), Some(Uint128::new(15)), deadline, &coins(40, ATOM)).unwrap();
+
+    contract.donate(&mut app, &sender1, &coins(20, ATOM)).unwrap();
+    contract.donate(&mut app, &sender1, &coins(20, ATOM)).unwrap();
+    contract.donate(&mut app, &sender2, &coins(80, ATOM)).unwrap();
+
+    let resp = contract.query_matching_pool(&app).unwrap();
+    assert_eq!(resp, MatchingPoolResp {
+        sponsor: sponsor.clone(),
+        remaining: Coin::new(10, ATOM),
+        matched: Uint128::new(30),
+        ratio: Decimal::percent(50),
+        donor_cap: Some(Uint128::new(15)),
+        deadline,
+    });
+
+    let err = contract.reclaim_matching_pool(&mut app, &sponsor).unwrap_err();
+    assert_eq!(err, ContractError::MatchingPoolActive { deadline });
+
+    contract.donate(&mut app, &sender3, &coins(40, ATOM)).unwrap();
+    let resp = contract.query_matching_pool(&app).unwrap();
+    assert_eq!(resp.remaining, Coin::new(0, ATOM));
+    assert_eq!(resp.matched, Uint128::new(40));
+
+    contract.withdraw(&mut app, &owner).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), coins(200, ATOM));
+
+    let err = contract.reclaim_matching_pool(&mut app, &owner).unwrap_err();
+    assert_eq!(err, ContractError::NotSponsor {});
+
+    app.update_block(|block| block.height += 10);
+    contract.reclaim_matching_pool(&mut app, &sponsor).unwrap();
+    assert!(contract.query_matching_pool(&app).is_err());
+}
+
+#[test]
+fn matching_pool_reclaim() {
+    let owner = Addr::unchecked("owner");
+    let sponsor = Addr::unchecked("sponsor");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router.bank.init_balance(storage, &sponsor, coins(40, ATOM)).unwrap();
+        router.bank.init_balance(storage, &sender, coins(40, ATOM)).unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            refund_window: Some(Duration::Height(30)),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let deadline = Expiration::AtHeight(app.block_info().height + 10);
+    contract.fund_matching_pool(&mut app, &sponsor, Decimal::percent(50), Some(Uint128::new(10)), deadline, &coins(40, ATOM)).unwrap();
+
+    contract.donate(&mut app, &sender, &coins(20, ATOM)).unwrap();
+    assert_eq!(contract.query_matching_pool(&app).unwrap().remaining, Coin::new(30, ATOM));
+
+    contract.withdraw(&mut app, &owner).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), vec![]);
+
+    contract.reclaim(&mut app, &sender, 1).unwrap();
+    let resp = contract.query_matching_pool(&app).unwrap();
+    assert_eq!(resp.remaining, Coin::new(40, ATOM));
+    assert_eq!(resp.matched, Uint128::zero());
+
+    contract.donate(&mut app, &sender, &coins(20, ATOM)).unwrap();
+    assert_eq!(contract.query_matching_pool(&app).unwrap().remaining, Coin::new(30, ATOM));
+
+    app.update_block(|block| block.height += 10);
+    contract.reclaim_matching_pool(&mut app, &sponsor).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&sponsor).unwrap(), coins(30, ATOM));
+
+    contract.reclaim(&mut app, &sender, 2).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&sender).unwrap(), coins(40, ATOM));
+    assert_eq!(app.wrap().query_all_balances(&sponsor).unwrap(), coins(40, ATOM));
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), vec![]);
+}
+
+#[test]
+fn matching_pool_replace_expired() {
+    let owner = Addr::unchecked("owner");
+    let sponsor = Addr::unchecked("sponsor");
+    let other_sponsor = Addr::unchecked("other_sponsor");
+
+    let mut app = App::new(|router, _api, storage| {
+        router.bank.init_balance(storage, &sponsor, coins(40, ATOM)).unwrap();
+        router.bank.init_balance(storage, &other_sponsor, coins(20, ATOM)).unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        None,
+    ).unwrap();
+
+    let deadline = Expiration::AtHeight(app.block_info().height + 10);
+    contract.fund_matching_pool(&mut app, &sponsor, Decimal::percent(50), None, deadline, &coins(40, ATOM)).unwrap();
+
+    let err = contract.fund_matching_pool(&mut app, &other_sponsor, Decimal::percent(50), None, deadline, &coins(20, ATOM)).unwrap_err();
+    assert_eq!(err, ContractError::MatchingPoolExists {});
+
+    app.update_block(|block| block.height += 10);
+    let deadline = Expiration::AtHeight(app.block_info().height + 10);
+    contract.fund_matching_pool(&mut app, &other_sponsor, Decimal::percent(50), None, deadline, &coins(20, ATOM)).unwrap();
+
+    let resp = contract.query_matching_pool(&app).unwrap();
+    assert_eq!(resp.sponsor, other_sponsor);
+    assert_eq!(resp.remaining, Coin::new(20, ATOM));
+    assert_eq!(app.wrap().query_all_balances(&sponsor).unwrap(), coins(40, ATOM));
+    assert_eq!(app.wrap().query_all_balances(contract.addr()).unwrap(), coins(20, ATOM));
+}
+
+#[test]
+fn campaigns() {
+    let owner = Addr::unchecked("owner");
+    let organizer = Addr::unchecked("organizer");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router.bank.init_balance(storage, &sender, coins(100, ATOM)).unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let parent_contract = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "parent contract",
+        Coin::new(0, ATOM),
+        None,
+    ).unwrap();
+    let contract = CountingContract::instantiate(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        Coin::new(10, ATOM),
+        None,
+    ).unwrap();
+
+    let parent = CampaignParent {
+        addr: parent_contract.addr().to_string(),
+        donating_period: 2,
+        part: Decimal::percent(10),
+    };
+    let err = contract.create_campaign(&mut app, &sender, "school", Coin::new(20, ATOM), Some(&organizer), Some(parent.clone())).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { required_role: Role::ConfigManager });
+
+    contract.create_campaign(&mut app, &owner, "school", Coin::new(20, ATOM), Some(&organizer), Some(parent.clone())).unwrap();
+
+    let err = contract.create_campaign(&mut app, &owner, "school", Coin::new(5, ATOM), None, None).unwrap_err();
+    assert_eq!(err, ContractError::CampaignExists { name: "school".to_owned() });
+
+    let err = contract.donate_campaign(&mut app, &sender, "library", &coins(20, ATOM)).unwrap_err();
+    assert_eq!(err, ContractError::UnknownCampaign { name: "library".to_owned() });
+
+    contract.donate_campaign(&mut app, &sender, "school", &coins(10, ATOM)).unwrap();
+    contract.donate_campaign(&mut app, &sender, "school", &coins(20, ATOM)).unwrap();
+    contract.donate_campaign(&mut app, &sender, "school", &coins(20, ATOM)).unwrap();
+    contract.donate(&mut app, &sender, &coins(10, ATOM)).unwrap();
+
+    let resp = contract.query_campaign(&app, "school").unwrap();
+    assert_eq!(resp.owner, organizer);
+    assert_eq!(resp.parent, Some(parent));
+    assert_eq!(resp.counter, 2);
+    assert_eq!(resp.balance, coins(45, ATOM));
+    assert_eq!(contract.query_value(&app).unwrap().value, 1);
+    assert_eq!(parent_contract.query_value(&app).unwrap().value, 1);
+    assert_eq!(app.wrap().query_all_balances(parent_contract.addr()).unwrap(), coins(5, ATOM));
+    assert_eq!(contract.query_contributions(&app, &sender).unwrap().contributions, coins(10, ATOM));
+
+    contract.withdraw(&mut app, &owner).unwrap();
+    assert_eq!(app.wrap().query_all_balances(&owner).unwrap(), coins(10, ATOM));
+
+    let err = contract.withdraw_campaign(&mut app, &owner, "school").unwrap_err();
+    assert_eq!(err, ContractError::NotCampaignOwner { name: "school".to_owned() });
+
+    contract.withdraw_campaign(&mut app, &organizer, "school").unwrap();
+    assert_eq!(app.wrap().query_all_balances(&organizer).unwrap(), coins(45, ATOM));
+    assert_eq!(contract.query_campaign(&app, "school").unwrap().balance, vec![]);
+}
+
+#[test]
+fn proportional_counting() {
+    let owner = Addr::unchecked("owner");
+    let sender = Addr::unchecked("sender");
+
+    let mut app = App::new(|router, _api, storage| {
+        router.bank.init_balance(storage, &sender, coins(20_300, ATOM)).unwrap();
+    });
+
+    let code_id = CountingContract::store_code(&mut app);
+    let contract = CountingContract::instantiate_with_msg(
+        &mut app, 
+        code_id, 
+        &owner, 
+        None,
+        "counting contract",
+        &InstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            refund_window: Some(Duration::Height(10)),
+            counting_mode: Some(CountingMode::Proportional { cap: Some(5) }),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let resp = contract.query_config(&app).unwrap();
+    assert_eq!(resp.minimal_donation, Coin::new(10, ATOM));
+    assert_eq!(resp.counting_mode, CountingMode::Proportional { cap: Some(5) });
+
+    contract.donate(&mut app, &sender, &coins(35, ATOM)).unwrap();
+    assert_eq!(contract.query_value(&app).unwrap().value, 3);
+
+    contract.donate(&mut app, &sender, &coins(100, ATOM)).unwrap();
+    assert_eq!(contract.query_value(&app).unwrap().value, 8);
+
+    contract.donate(&mut app, &sender, &coins(5, ATOM)).unwrap();
+    assert_eq!(contract.query_value(&app).unwrap().value, 8);
+
+    contract.reclaim(&mut app, &sender, 1).unwrap();
+    assert_eq!(contract.query_value(&app).unwrap().value, 5);
+
+    let err = contract.update_counting_mode(&mut app, &sender, CountingMode::Flat).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized { required_role: Role::ConfigManager });
+
+    contract.update_counting_mode(&mut app, &owner, CountingMode::Flat).unwrap();
+    assert_eq!(contract.query_config(&app).unwrap().counting_mode, CountingMode::Flat);
+
+    contract.donate(&mut app, &sender, &coins(100, ATOM)).unwrap();
+    assert_eq!(contract.query_value(&app).unwrap().value, 6);
+
+    let err = contract.update_counting_mode(&mut app, &owner, CountingMode::Proportional { cap: Some(1_001) }).unwrap_err();
+    assert_eq!(err, ContractError::InvalidCountingCap { cap: 1_001, max: 1_000 });
+
+    contract.update_counting_mode(&mut app, &owner, CountingMode::Proportional { cap: None }).unwrap();
+    contract.donate(&mut app, &sender, &coins(20_000, ATOM)).unwrap();
+    assert_eq!(contract.query_value(&app).unwrap().value, 1_006);
+}
@@ -1,11 +1,13 @@
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Coin, Addr, Decimal};
-use cw_storage_plus::Item;
+use cosmwasm_std::{Coin, Addr, Decimal, Empty, Timestamp, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, SnapshotMap, MultiIndex, SnapshotItem, Strategy};
+use cw_utils::{Duration, Expiration};
+
+use crate::msg::{AccessMode, CountingMode, Governance, MilestoneAction, RateLimit, IbcParent, Role, Sink, Unbonding, VoteOption, VoucherKey, WithdrawLimits, ProposalAction, IbcCounterparty};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct State {
-    pub counter: u64,
     pub minimal_donation: Coin,
     pub donating_parent: Option<u64>,
 }
@@ -15,8 +17,334 @@ pub struct ParentDonation {
     pub address: Addr,
     pub donating_parent_period: u64,
     pub part: Decimal,
+    #[serde(default)]
+    pub payout_interval: Option<Duration>,
+    #[serde(default)]
+    pub keeper_fee: Option<Decimal>,
+    #[serde(default)]
+    pub ibc: Option<IbcParent>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RefundableDonation {
+    pub donor: Addr,
+    pub funds: Vec<Coin>,
+    pub counted: bool,
+    #[serde(default)]
+    pub count: u64,
+    #[serde(default)]
+    pub beneficiary: Option<Addr>,
+    pub expires: Expiration,
+    #[serde(default)]
+    pub matched: Option<MatchedDonation>,
+    #[serde(default)]
+    pub referral: Option<Referral>,
+}
+
+impl RefundableDonation {
+    pub fn locked(&self) -> Vec<Coin> {
+        let mut locked = self.funds.clone();
+        if let Some(matched) = &self.matched {
+            locked.push(matched.amount.clone());
+        }
+        locked
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Referral {
+    pub referrer: Addr,
+    pub reward: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MatchedDonation {
+    pub pool_id: u64,
+    pub sponsor: Addr,
+    pub amount: Coin,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct VestingSchedule {
+    pub start: Timestamp,
+    pub duration: u64,
+    pub cliff: u64,
+}
+
+impl VestingSchedule {
+    pub fn vested(&self, total: Uint128, now: Timestamp) -> Uint128 {
+        let elapsed = now.seconds().saturating_sub(self.start.seconds());
+        if elapsed < self.cliff {
+            Uint128::zero()
+        } else if elapsed >= self.duration {
+            total
+        } else {
+            total.multiply_ratio(elapsed, self.duration)
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WithdrawWindow {
+    pub start: Timestamp,
+    pub withdrawn: Vec<Coin>,
+    pub last_withdraw: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OwnerSet {
+    pub members: Vec<Addr>,
+    pub threshold: u64,
+    pub proposal_duration: Duration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Proposal {
+    pub proposer: Addr,
+    pub action: ProposalAction,
+    pub approvals: Vec<Addr>,
+    pub expires: Expiration,
+    pub executed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Roles {
+    pub treasurer: bool,
+    pub config_manager: bool,
+    pub pauser: bool,
+}
+
+impl Roles {
+    pub fn has(&self, role: Role) -> bool {
+        match role {
+            Role::Owner => false,
+            Role::Treasurer => self.treasurer,
+            Role::ConfigManager => self.config_manager,
+            Role::Pauser => self.pauser,
+        }
+    }
+
+    pub fn set(&mut self, role: Role, granted: bool) {
+        match role {
+            Role::Owner => (),
+            Role::Treasurer => self.treasurer = granted,
+            Role::ConfigManager => self.config_manager = granted,
+            Role::Pauser => self.pauser = granted,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SpendingProposal {
+    pub proposer: Addr,
+    pub description: String,
+    pub amount: Vec<Coin>,
+    pub recipient: Addr,
+    pub yes: Uint128,
+    pub no: Uint128,
+    pub abstain: Uint128,
+    pub total_weight: Uint128,
+    pub height: u64,
+    pub expires: Expiration,
+    pub executed: bool,
+}
+
+impl SpendingProposal {
+    pub fn passed(&self, governance: &Governance) -> bool {
+        let voted = self.yes + self.no + self.abstain;
+        if self.total_weight.is_zero() || Decimal::from_ratio(voted, self.total_weight) < governance.quorum {
+            return false;
+        }
+
+        let decisive = self.yes + self.no;
+        !decisive.is_zero() && Decimal::from_ratio(self.yes, decisive) >= governance.threshold
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BeneficiaryShare {
+    pub address: Addr,
+    pub weight: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PendingShare {
+    pub transfer_channel_id: String,
+    pub remote_address: String,
+    pub funds: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IbcDonation {
+    pub donations: u64,
+    pub funds: Vec<Coin>,
+    #[serde(default)]
+    pub received: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MatchingPool {
+    #[serde(default)]
+    pub id: u64,
+    pub sponsor: Addr,
+    pub remaining: Coin,
+    pub matched: Uint128,
+    pub ratio: Decimal,
+    pub donor_cap: Option<Uint128>,
+    pub deadline: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CampaignParentDonation {
+    pub address: Addr,
+    pub donating_period: u64,
+    pub part: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Campaign {
+    pub owner: Addr,
+    pub minimal_donation: Coin,
+    pub parent: Option<CampaignParentDonation>,
+    pub counter: u64,
+    pub balance: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Donation {
+    pub donor: Addr,
+    pub funds: Vec<Coin>,
+    pub memo: Option<String>,
+    pub campaign: Option<String>,
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+pub struct DonationIndexes<'a> {
+    pub donor: MultiIndex<'a, Addr, Donation, u64>,
+}
+
+impl<'a> IndexList<Donation> for DonationIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Donation>> + '_> {
+        let indexes: Vec<&dyn Index<Donation>> = vec![&self.donor];
+        Box::new(indexes.into_iter())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DonorTotal {
+    pub donor: Addr,
+    pub denom: String,
+    pub amount: Uint128,
+}
+
+pub struct DonorTotalIndexes<'a> {
+    pub amount: MultiIndex<'a, (String, u128), DonorTotal, (Addr, String)>,
+}
+
+impl<'a> IndexList<DonorTotal> for DonorTotalIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<DonorTotal>> + '_> {
+        let indexes: Vec<&dyn Index<DonorTotal>> = vec![&self.amount];
+        Box::new(indexes.into_iter())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct DonorActivity {
+    pub cooldown_ends: Option<Expiration>,
+    pub window_ends: Option<Expiration>,
+    pub window_counted: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MilestoneStatus {
+    pub action: MilestoneAction,
+    pub reached_at: Option<u64>,
+    #[serde(default)]
+    pub failed: Option<String>,
 }
 
 pub const PARENT_DONATION: Item<ParentDonation> = Item::new("parent_donation");
+pub const NEXT_PAYOUT: Item<Expiration> = Item::new("next_payout");
+pub const PAYOUT_PENDING: Item<bool> = Item::new("payout_pending");
 pub const STATE: Item<State> = Item::new("state");
-pub const OWNER: Item<Addr> = Item::new("owner");
\ No newline at end of file
+pub const OWNERS: Item<OwnerSet> = Item::new("owners");
+pub const ROLES: Map<&Addr, Roles> = Map::new("roles");
+pub const PAUSED: Item<bool> = Item::new("paused");
+pub const LAST_PROPOSAL_ID: Item<u64> = Item::new("last_proposal_id");
+pub const PROPOSALS: Map<u64, Proposal> = Map::new("proposals");
+pub const REFUND_WINDOW: Item<Duration> = Item::new("refund_window");
+pub const LAST_DONATION_ID: Item<u64> = Item::new("last_donation_id");
+pub const REFUNDABLE: Map<u64, RefundableDonation> = Map::new("refundable");
+pub const LOCKED_REFUNDS: Item<Vec<Coin>> = Item::new("locked_refunds");
+pub const VESTING: Item<VestingSchedule> = Item::new("vesting");
+pub const WITHDRAWN: Map<&str, Uint128> = Map::new("withdrawn");
+pub const WITHDRAW_LIMITS: Item<WithdrawLimits> = Item::new("withdraw_limits");
+pub const WITHDRAW_WINDOW: Item<WithdrawWindow> = Item::new("withdraw_window");
+pub const WITHDRAW_RECIPIENT: Item<Addr> = Item::new("withdraw_recipient");
+pub const CONTRIBUTIONS: SnapshotMap<(&Addr, &str), Uint128> = SnapshotMap::new(
+    "contributions",
+    "contributions__checkpoints",
+    "contributions__changelog",
+    Strategy::EveryBlock,
+);
+pub const TOTAL_CONTRIBUTED: SnapshotMap<&str, Uint128> = SnapshotMap::new(
+    "total_contributed",
+    "total_contributed__checkpoints",
+    "total_contributed__changelog",
+    Strategy::EveryBlock,
+);
+pub const GOVERNANCE: Item<Governance> = Item::new("governance");
+pub const LAST_SPENDING_PROPOSAL_ID: Item<u64> = Item::new("last_spending_proposal_id");
+pub const SPENDING_PROPOSALS: Map<u64, SpendingProposal> = Map::new("spending_proposals");
+pub const VOTES: Map<(u64, &Addr), VoteOption> = Map::new("votes");
+pub const BENEFICIARIES: Item<Vec<BeneficiaryShare>> = Item::new("beneficiaries");
+pub const UNBONDING_PERIOD: Item<u64> = Item::new("unbonding_period");
+pub const UNBONDING: Item<Vec<Unbonding>> = Item::new("unbonding");
+pub const SINKS: Item<Vec<Sink>> = Item::new("sinks");
+pub const IBC_TIMEOUT: Item<u64> = Item::new("ibc_timeout");
+pub const IBC_DESTINATIONS: Map<&str, String> = Map::new("ibc_destinations");
+pub const LAST_PACKET_ID: Item<u64> = Item::new("last_packet_id");
+pub const PENDING_SHARES: Map<u64, PendingShare> = Map::new("pending_shares");
+pub const IBC_COUNTERPARTIES: Map<&str, IbcCounterparty> = Map::new("ibc_counterparties");
+pub const IBC_CHANNELS: Map<&str, String> = Map::new("ibc_channels");
+pub const IBC_DONATIONS: Map<(&str, u64), IbcDonation> = Map::new("ibc_donations");
+pub const VOUCHER_KEY: Item<VoucherKey> = Item::new("voucher_key");
+pub const REDEEMED_VOUCHERS: Map<u64, Addr> = Map::new("redeemed_vouchers");
+pub const MATCHING_POOL: Item<MatchingPool> = Item::new("matching_pool");
+pub const LAST_MATCHING_POOL_ID: Item<u64> = Item::new("last_matching_pool_id");
+pub const MATCHED: Map<(u64, &Addr), Uint128> = Map::new("pool_matched");
+pub const ROUND_MANAGER: Item<Addr> = Item::new("round_manager");
+pub const ROUND_CONTRIBUTIONS: Map<(&Addr, &str), Uint128> = Map::new("round_contributions");
+pub const CAMPAIGNS: Map<&str, Campaign> = Map::new("campaigns");
+pub const CAMPAIGN_BALANCE: Item<Vec<Coin>> = Item::new("campaign_balance");
+pub const COUNTING_MODE: Item<CountingMode> = Item::new("counting_mode");
+pub const COUNTER_HISTORY: SnapshotItem<u64> = SnapshotItem::new(
+    "counter_history",
+    "counter_history__checkpoints",
+    "counter_history__changelog",
+    Strategy::EveryBlock,
+);
+pub const LAST_LOGGED_DONATION_ID: Item<u64> = Item::new("last_logged_donation_id");
+pub const REFERRAL_PART: Item<Decimal> = Item::new("referral_part");
+pub const REFERRALS: Map<(&Addr, &str), Uint128> = Map::new("referrals");
+pub const ACCESS_MODE: Item<AccessMode> = Item::new("access_mode");
+pub const ALLOWLIST: Map<&Addr, Empty> = Map::new("allowlist");
+pub const BLOCKLIST: Map<&Addr, Empty> = Map::new("blocklist");
+pub const RATE_LIMIT: Item<RateLimit> = Item::new("rate_limit");
+pub const DONOR_ACTIVITY: Map<&Addr, DonorActivity> = Map::new("donor_activity");
+pub const MILESTONES: Map<u64, MilestoneStatus> = Map::new("milestones");
+
+pub fn donation_log<'a>() -> IndexedMap<'a, u64, Donation, DonationIndexes<'a>> {
+    let indexes = DonationIndexes {
+        donor: MultiIndex::new(|_, donation| donation.donor.clone(), "donations", "donations__donor"),
+    };
+    IndexedMap::new("donations", indexes)
+}
+
+pub fn donor_totals<'a>() -> IndexedMap<'a, (&'a Addr, &'a str), DonorTotal, DonorTotalIndexes<'a>> {
+    let indexes = DonorTotalIndexes {
+        amount: MultiIndex::new(|_, total| (total.denom.clone(), total.amount.u128()), "donor_totals", "donor_totals__amount"),
+    };
+    IndexedMap::new("donor_totals", indexes)
+}
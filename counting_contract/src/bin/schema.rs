@@ -1,5 +1,5 @@
 use cosmwasm_schema::write_api;
-use counting_contract::msg::{InstantiateMsg, ExecMsg, QueryMsg};
+use counting_contract_0_1::msg::{InstantiateMsg, ExecMsg, QueryMsg};
 
 fn main() {
     write_api! {
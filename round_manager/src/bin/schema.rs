@@ -0,0 +1,10 @@
+use cosmwasm_schema::write_api;
+use round_manager::msg::{InstantiateMsg, ExecMsg, QueryMsg};
+
+fn main() {
+    write_api! {
+        instantiate: InstantiateMsg,
+        execute: ExecMsg,
+        query: QueryMsg,
+    }
+}
\ No newline at end of file
@@ -0,0 +1,198 @@
+use cosmwasm_std::{Addr, Decimal256, Deps, DepsMut, MessageInfo, Order, Response, StdResult, Uint128, Uint256};
+use cw2::set_contract_version;
+
+use crate::msg::InstantiateMsg;
+use crate::state::{Project, Round, ROUND, PROJECTS};
+
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub fn instantiate(deps: DepsMut, info: MessageInfo, msg: InstantiateMsg) -> StdResult<Response> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    ROUND.save(
+        deps.storage,
+        &Round {
+            admin: info.sender,
+            denom: msg.denom,
+            end: msg.end,
+            pool: Uint128::zero(),
+            distributed: false,
+        },
+    )?;
+    Ok(Response::new())
+}
+
+fn contribution_sqrt(amount: Uint128) -> Decimal256 {
+    Decimal256::from_ratio(amount, 1u128).sqrt()
+}
+
+fn quadratic_weight(project: &Project) -> Uint256 {
+    let squared = Uint256::from(1u128) * (project.sqrt_sum * project.sqrt_sum);
+    squared.saturating_sub(Uint256::from(project.total))
+}
+
+fn matches(deps: Deps) -> StdResult<Vec<(Addr, Project, Uint128)>> {
+    let pool = ROUND.load(deps.storage)?.pool;
+    let projects = PROJECTS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let total = projects.iter().fold(Uint256::zero(), |total, (_, project)| total + quadratic_weight(project));
+    projects.into_iter().map(|(contract, project)| {
+        let matched = if total.is_zero() {
+            Uint128::zero()
+        } else {
+            Uint256::from(pool).multiply_ratio(quadratic_weight(&project), total).try_into()?
+        };
+        Ok((contract, project, matched))
+    }).collect()
+}
+
+pub mod query {
+    use cosmwasm_std::{Deps, StdError, StdResult};
+
+    use crate::msg::{RoundResp, ProjectResp, ProjectsResp};
+    use crate::state::ROUND;
+
+    pub fn round(deps: Deps) -> StdResult<RoundResp> {
+        let round = ROUND.load(deps.storage)?;
+        Ok(RoundResp {
+            admin: round.admin,
+            denom: round.denom,
+            end: round.end,
+            pool: round.pool,
+            distributed: round.distributed,
+        })
+    }
+
+    pub fn project(deps: Deps, contract: String) -> StdResult<ProjectResp> {
+        let contract = deps.api.addr_validate(&contract)?;
+        projects(deps)?
+            .projects
+            .into_iter()
+            .find(|project| project.contract == contract)
+            .ok_or_else(|| StdError::not_found("project"))
+    }
+
+    pub fn projects(deps: Deps) -> StdResult<ProjectsResp> {
+        let projects = super::matches(deps)?
+            .into_iter()
+            .map(|(contract, project, matched)| ProjectResp { contract, total: project.total, donors: project.donors, matched })
+            .collect();
+        Ok(ProjectsResp { projects })
+    }
+}
+
+pub mod exec {
+    use cosmwasm_std::{BankMsg, Coin, DepsMut, Env, MessageInfo, Response};
+
+    use crate::error::ContractError;
+    use crate::state::{Project, ROUND, PROJECTS, CONTRIBUTIONS};
+
+    use super::contribution_sqrt;
+
+    pub fn register_project(deps: DepsMut, env: Env, info: MessageInfo, contract: String) -> Result<Response, ContractError> {
+        let round = ROUND.load(deps.storage)?;
+        if info.sender != round.admin {
+            return Err(ContractError::Unauthorized {});
+        }
+        if round.end.is_expired(&env.block) {
+            return Err(ContractError::RoundEnded {});
+        }
+
+        let contract = deps.api.addr_validate(&contract)?;
+        if !PROJECTS.has(deps.storage, &contract) {
+            PROJECTS.save(deps.storage, &contract, &Project::default())?;
+        }
+
+        let resp = Response::new()
+            .add_attribute("action", "register_project")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("project", contract.as_str());
+        Ok(resp)
+    }
+
+    pub fn fund_pool(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+        let mut round = ROUND.load(deps.storage)?;
+        if round.distributed {
+            return Err(ContractError::AlreadyDistributed {});
+        }
+
+        for coin in &info.funds {
+            if coin.denom != round.denom {
+                return Err(ContractError::InvalidFunds { denom: round.denom });
+            }
+            round.pool += coin.amount;
+        }
+        ROUND.save(deps.storage, &round)?;
+
+        let resp = Response::new()
+            .add_attribute("action", "fund_pool")
+            .add_attribute("sender", info.sender.as_str())
+            .add_attribute("pool", round.pool.to_string());
+        Ok(resp)
+    }
+
+    pub fn donation_hook(deps: DepsMut, env: Env, info: MessageInfo, donor: String, totals: Vec<Coin>) -> Result<Response, ContractError> {
+        let round = ROUND.load(deps.storage)?;
+        let mut project = PROJECTS
+            .may_load(deps.storage, &info.sender)?
+            .ok_or_else(|| ContractError::NotRegistered { contract: info.sender.clone() })?;
+
+        let resp = Response::new()
+            .add_attribute("action", "donation_hook")
+            .add_attribute("sender", info.sender.as_str());
+        if round.end.is_expired(&env.block) {
+            return Ok(resp.add_attribute("ignored", "round_ended"));
+        }
+
+        let donor = deps.api.addr_validate(&donor)?;
+        let total = totals.into_iter().find(|coin| coin.denom == round.denom).map(|coin| coin.amount).unwrap_or_default();
+        let previous = CONTRIBUTIONS.may_load(deps.storage, (&info.sender, &donor))?.unwrap_or_default();
+        if total.is_zero() {
+            CONTRIBUTIONS.remove(deps.storage, (&info.sender, &donor));
+        } else {
+            CONTRIBUTIONS.save(deps.storage, (&info.sender, &donor), &total)?;
+        }
+
+        match (previous.is_zero(), total.is_zero()) {
+            (true, false) => project.donors += 1,
+            (false, true) => project.donors -= 1,
+            _ => (),
+        }
+        project.total = project.total - previous + total;
+        project.sqrt_sum = project.sqrt_sum - contribution_sqrt(previous) + contribution_sqrt(total);
+        PROJECTS.save(deps.storage, &info.sender, &project)?;
+
+        Ok(resp.add_attribute("donor", donor.as_str()).add_attribute("total", total.to_string()))
+    }
+
+    pub fn distribute(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        let mut round = ROUND.load(deps.storage)?;
+        if !round.end.is_expired(&env.block) {
+            return Err(ContractError::RoundOpen { end: round.end });
+        }
+        if round.distributed {
+            return Err(ContractError::AlreadyDistributed {});
+        }
+
+        let msgs = super::matches(deps.as_ref())?
+            .into_iter()
+            .filter(|(_, _, matched)| !matched.is_zero())
+            .map(|(project, _, matched)| BankMsg::Send {
+                to_address: project.to_string(),
+                amount: vec![Coin { denom: round.denom.clone(), amount: matched }],
+            })
+            .collect::<Vec<_>>();
+
+        round.distributed = true;
+        ROUND.save(deps.storage, &round)?;
+
+        let resp = Response::new()
+            .add_messages(msgs)
+            .add_attribute("action", "distribute")
+            .add_attribute("sender", info.sender.as_str());
+        Ok(resp)
+    }
+}
@@ -0,0 +1,33 @@
+use cosmwasm_std::{Addr, StdError};
+use cw_utils::Expiration;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized - only round admin can register projects")]
+    Unauthorized {},
+
+    #[error("Contract {contract} is not registered in this round")]
+    NotRegistered {
+        contract: Addr,
+    },
+
+    #[error("Round ended")]
+    RoundEnded {},
+
+    #[error("Round is open until {end}")]
+    RoundOpen {
+        end: Expiration,
+    },
+
+    #[error("Matching pool already distributed")]
+    AlreadyDistributed {},
+
+    #[error("Matching pool accepts only {denom}")]
+    InvalidFunds {
+        denom: String,
+    },
+}
@@ -0,0 +1,55 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use cosmwasm_std::{DepsMut, Deps, Env, MessageInfo, StdResult, Response, Binary, to_json_binary};
+use error::ContractError;
+use msg::InstantiateMsg;
+
+mod contract;
+pub mod msg;
+pub mod error;
+mod state;
+#[cfg(any(test, feature = "tests"))]
+pub mod multitest;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    contract::instantiate(deps, info, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(
+    deps: Deps,
+    _env: Env,
+    msg: msg::QueryMsg,
+) -> StdResult<Binary> {
+    use msg::QueryMsg::*;
+
+    match msg {
+        Round {} => to_json_binary(&contract::query::round(deps)?),
+        Project { contract } => to_json_binary(&contract::query::project(deps, contract)?),
+        Projects {} => to_json_binary(&contract::query::projects(deps)?),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: msg::ExecMsg,
+) -> Result<Response, ContractError> {
+    use msg::ExecMsg::*;
+
+    match msg {
+        RegisterProject { contract } => contract::exec::register_project(deps, env, info, contract),
+        FundPool {} => contract::exec::fund_pool(deps, info),
+        DonationHook { donor, totals } => contract::exec::donation_hook(deps, env, info, donor, totals),
+        Distribute {} => contract::exec::distribute(deps, env, info),
+    }
+}
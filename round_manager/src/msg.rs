@@ -0,0 +1,50 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Coin, Uint128};
+use cw_utils::Expiration;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub denom: String,
+    pub end: Expiration,
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(RoundResp)]
+    Round {},
+    #[returns(ProjectResp)]
+    Project { contract: String },
+    #[returns(ProjectsResp)]
+    Projects {},
+}
+
+#[cw_serde]
+pub enum ExecMsg {
+    RegisterProject { contract: String },
+    FundPool {},
+    DonationHook { donor: String, totals: Vec<Coin> },
+    Distribute {},
+}
+
+#[cw_serde]
+pub struct RoundResp {
+    pub admin: Addr,
+    pub denom: String,
+    pub end: Expiration,
+    pub pool: Uint128,
+    pub distributed: bool,
+}
+
+#[cw_serde]
+pub struct ProjectResp {
+    pub contract: Addr,
+    pub total: Uint128,
+    pub donors: u64,
+    pub matched: Uint128,
+}
+
+#[cw_serde]
+pub struct ProjectsResp {
+    pub projects: Vec<ProjectResp>,
+}
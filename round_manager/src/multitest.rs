@@ -0,0 +1,103 @@
+use cosmwasm_std::{Addr, Coin, StdResult};
+use cw_multi_test::{App, Executor, ContractWrapper};
+use cw_utils::Expiration;
+
+use crate::msg::{InstantiateMsg, QueryMsg, ExecMsg, RoundResp, ProjectResp, ProjectsResp};
+use crate::{ContractError, execute, instantiate, query};
+
+#[cfg(test)]
+mod tests;
+pub struct RoundManager(Addr);
+
+impl RoundManager {
+    pub fn addr(&self) -> &Addr {
+        &self.0
+    }
+
+    pub fn store_code(app: &mut App) -> u64 {
+        let contract = ContractWrapper::new(
+            execute,
+            instantiate,
+            query,
+        );
+        app.store_code(Box::new(contract))
+    }
+
+    #[track_caller]
+    pub fn instantiate(
+        app: &mut App, 
+        code_id: u64, 
+        sender: &Addr,
+        label: &str,
+        denom: &str,
+        end: Expiration,
+    ) -> StdResult<RoundManager> {
+        app.instantiate_contract(
+            code_id, 
+            sender.clone(), 
+            &InstantiateMsg {
+                denom: denom.to_owned(),
+                end,
+            }, 
+            &[], 
+            label, 
+            None,
+        )
+        .map_err(|err| err.downcast().unwrap())
+        .map(RoundManager)
+    }
+
+    #[track_caller]
+    pub fn register_project(&self, app: &mut App, sender: &Addr, contract: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::RegisterProject { contract: contract.to_string() }, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn fund_pool(&self, app: &mut App, sender: &Addr, funds: &[Coin]) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::FundPool {}, 
+            funds,
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    #[track_caller]
+    pub fn distribute(&self, app: &mut App, sender: &Addr) -> Result<(), ContractError> {
+        app.execute_contract(
+            sender.clone(), 
+            self.0.clone(), 
+            &ExecMsg::Distribute {}, 
+            &[],
+        )
+        .map_err(|err| err.downcast::<ContractError>().unwrap())?;
+
+        Ok(())
+    }
+
+    pub fn query_round(&self, app: &App) -> StdResult<RoundResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::Round {})
+    }
+
+    pub fn query_project(&self, app: &App, contract: &Addr) -> StdResult<ProjectResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::Project { contract: contract.to_string() })
+    }
+
+    pub fn query_projects(&self, app: &App) -> StdResult<ProjectsResp> {
+        app.wrap()
+        .query_wasm_smart(self.0.clone(), &QueryMsg::Projects {})
+    }
+}
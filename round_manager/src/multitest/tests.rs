@@ -0,0 +1,147 @@
+use cosmwasm_std::{Addr, Coin, coins, Uint128};
+use counting_contract::msg::InstantiateMsg as CountingInstantiateMsg;
+use counting_contract::multitest::CountingContract;
+use cw_multi_test::App;
+use cw_utils::{Duration, Expiration};
+
+use crate::error::ContractError;
+use crate::msg::ProjectResp;
+use crate::multitest::RoundManager;
+
+const ATOM: &str = "atom";
+
+#[test]
+fn quadratic_matching() {
+    let owner = Addr::unchecked("owner");
+    let admin = Addr::unchecked("admin");
+    let sponsor = Addr::unchecked("sponsor");
+    let donors: Vec<Addr> = (1..=5).map(|idx| Addr::unchecked(format!("donor{idx}"))).collect();
+
+    let mut app = App::new(|router, _api, storage| {
+        router.bank.init_balance(storage, &sponsor, coins(1000, ATOM)).unwrap();
+        for donor in &donors {
+            router.bank.init_balance(storage, donor, coins(100, ATOM)).unwrap();
+        }
+    });
+
+    let counting_id = CountingContract::store_code(&mut app);
+    let project_a = CountingContract::instantiate(&mut app, counting_id, &owner, None, "project a", Coin::new(10, ATOM), None).unwrap();
+    let project_b = CountingContract::instantiate(&mut app, counting_id, &owner, None, "project b", Coin::new(10, ATOM), None).unwrap();
+
+    let manager_id = RoundManager::store_code(&mut app);
+    let end = Expiration::AtHeight(app.block_info().height + 10);
+    let manager = RoundManager::instantiate(&mut app, manager_id, &admin, "round manager", ATOM, end).unwrap();
+
+    let err = manager.register_project(&mut app, &owner, project_a.addr()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    for project in [&project_a, &project_b] {
+        manager.register_project(&mut app, &admin, project.addr()).unwrap();
+        project.update_round_manager(&mut app, &owner, Some(manager.addr())).unwrap();
+    }
+    manager.fund_pool(&mut app, &sponsor, &coins(1000, ATOM)).unwrap();
+
+    project_a.donate(&mut app, &donors[0], &coins(50, ATOM)).unwrap();
+    project_a.donate(&mut app, &donors[0], &coins(50, ATOM)).unwrap();
+    project_a.donate(&mut app, &donors[1], &coins(100, ATOM)).unwrap();
+    project_a.donate(&mut app, &donors[2], &coins(100, ATOM)).unwrap();
+    project_b.donate(&mut app, &donors[3], &coins(100, ATOM)).unwrap();
+    project_b.donate(&mut app, &donors[4], &coins(100, ATOM)).unwrap();
+
+    let resp = manager.query_project(&app, project_a.addr()).unwrap();
+    assert_eq!(resp, ProjectResp {
+        contract: project_a.addr().clone(),
+        total: Uint128::new(300),
+        donors: 3,
+        matched: Uint128::new(750),
+    });
+    let resp = manager.query_project(&app, project_b.addr()).unwrap();
+    assert_eq!(resp.matched, Uint128::new(250));
+
+    let err = manager.distribute(&mut app, &admin).unwrap_err();
+    assert_eq!(err, ContractError::RoundOpen { end });
+
+    app.update_block(|block| block.height += 10);
+    manager.distribute(&mut app, &admin).unwrap();
+
+    assert_eq!(app.wrap().query_all_balances(project_a.addr()).unwrap(), coins(1050, ATOM));
+    assert_eq!(app.wrap().query_all_balances(project_b.addr()).unwrap(), coins(450, ATOM));
+    assert!(manager.query_round(&app).unwrap().distributed);
+
+    let err = manager.distribute(&mut app, &admin).unwrap_err();
+    assert_eq!(err, ContractError::AlreadyDistributed {});
+}
+
+#[test]
+fn reclaimed_donations() {
+    let owner = Addr::unchecked("owner");
+    let admin = Addr::unchecked("admin");
+    let sponsor = Addr::unchecked("sponsor");
+    let donor = Addr::unchecked("donor");
+
+    let mut app = App::new(|router, _api, storage| {
+        router.bank.init_balance(storage, &sponsor, coins(1000, ATOM)).unwrap();
+        router.bank.init_balance(storage, &donor, coins(100, ATOM)).unwrap();
+    });
+
+    let counting_id = CountingContract::store_code(&mut app);
+    let project = CountingContract::instantiate_with_msg(
+        &mut app,
+        counting_id,
+        &owner,
+        None,
+        "project",
+        &CountingInstantiateMsg {
+            minimal_donation: Coin::new(10, ATOM),
+            refund_window: Some(Duration::Height(10)),
+            ..Default::default()
+        },
+    ).unwrap();
+
+    let manager_id = RoundManager::store_code(&mut app);
+    let end = Expiration::AtHeight(app.block_info().height + 10);
+    let manager = RoundManager::instantiate(&mut app, manager_id, &admin, "round manager", ATOM, end).unwrap();
+
+    manager.register_project(&mut app, &admin, project.addr()).unwrap();
+    project.update_round_manager(&mut app, &owner, Some(manager.addr())).unwrap();
+    manager.fund_pool(&mut app, &sponsor, &coins(1000, ATOM)).unwrap();
+
+    project.donate(&mut app, &donor, &coins(100, ATOM)).unwrap();
+    let resp = manager.query_project(&app, project.addr()).unwrap();
+    assert_eq!(resp.total, Uint128::new(100));
+    assert_eq!(resp.donors, 1);
+
+    project.reclaim(&mut app, &donor, 1).unwrap();
+    let resp = manager.query_project(&app, project.addr()).unwrap();
+    assert_eq!(resp, ProjectResp {
+        contract: project.addr().clone(),
+        total: Uint128::zero(),
+        donors: 0,
+        matched: Uint128::zero(),
+    });
+}
+
+#[test]
+fn failing_round_hook() {
+    let owner = Addr::unchecked("owner");
+    let admin = Addr::unchecked("admin");
+    let donor = Addr::unchecked("donor");
+
+    let mut app = App::new(|router, _api, storage| {
+        router.bank.init_balance(storage, &donor, coins(100, ATOM)).unwrap();
+    });
+
+    let counting_id = CountingContract::store_code(&mut app);
+    let project = CountingContract::instantiate(&mut app, counting_id, &owner, None, "project", Coin::new(10, ATOM), None).unwrap();
+
+    let manager_id = RoundManager::store_code(&mut app);
+    let end = Expiration::AtHeight(app.block_info().height + 10);
+    let manager = RoundManager::instantiate(&mut app, manager_id, &admin, "round manager", ATOM, end).unwrap();
+
+    project.update_round_manager(&mut app, &owner, Some(manager.addr())).unwrap();
+    project.donate(&mut app, &donor, &coins(100, ATOM)).unwrap();
+
+    assert_eq!(project.query_value(&app).unwrap().value, 1);
+    assert_eq!(app.wrap().query_all_balances(project.addr()).unwrap(), coins(100, ATOM));
+    manager.query_project(&app, project.addr()).unwrap_err();
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Decimal256, Uint128};
+use cw_storage_plus::{Item, Map};
+use cw_utils::Expiration;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Round {
+    pub admin: Addr,
+    pub denom: String,
+    pub end: Expiration,
+    pub pool: Uint128,
+    pub distributed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct Project {
+    pub total: Uint128,
+    pub donors: u64,
+    pub sqrt_sum: Decimal256,
+}
+
+pub const ROUND: Item<Round> = Item::new("round");
+pub const PROJECTS: Map<&Addr, Project> = Map::new("projects");
+pub const CONTRIBUTIONS: Map<(&Addr, &Addr), Uint128> = Map::new("contributions");